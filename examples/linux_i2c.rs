@@ -0,0 +1,34 @@
+//! Print the conversions of an ADS122C04 connected to the I2C bus of a Raspberry Pi
+//!
+//! `cargo run --example linux_i2c --features linux -- /dev/i2c-1 0x40`
+
+use std::error::Error;
+
+use ads122x04::linux::linux_delay;
+use ads122x04::registers::{ConversionMode, Gain, Mux};
+use ads122x04::ADS122x04;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| "/dev/i2c-1".into());
+    let address = match args.next() {
+        Some(address) => u8::from_str_radix(address.trim_start_matches("0x"), 16)?,
+        None => 0x40,
+    };
+
+    let mut adc = ADS122x04::open_i2c(&path, address)?;
+    let mut delay = linux_delay();
+    adc.reset(&mut delay)?;
+    adc.set_input_mux(Mux::Ain0Ain1)?;
+    adc.set_gain(Gain::Gain1)?;
+    adc.set_conversion_mode(ConversionMode::Continuous)?;
+    adc.start()?;
+
+    loop {
+        while !adc.get_data_ready()? {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let result = adc.read_conversion()?;
+        println!("{} -> {:.6} V", result, adc.convert_raw_to_voltage(result.raw));
+    }
+}
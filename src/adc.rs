@@ -0,0 +1,70 @@
+//! embedded-hal 0.2 ADC traits, available with the `embedded-hal-02` feature
+//!
+//! embedded-hal 1.0 dropped the ADC traits, so they are only implemented for the 0.2 generation.
+
+use embedded_hal_02::adc::{Channel, OneShot};
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{Error, ADS122x04};
+
+/// AIN0 measured against AVSS
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ain0;
+
+/// AIN1 measured against AVSS
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ain1;
+
+/// AIN2 measured against AVSS
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ain2;
+
+/// AIN3 measured against AVSS
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ain3;
+
+macro_rules! impl_channel {
+    ($pin:ident, $mux:ident) => {
+        impl<BUS> Channel<ADS122x04<BUS>> for $pin {
+            type ID = Mux;
+
+            fn channel() -> Mux {
+                Mux::$mux
+            }
+        }
+    };
+}
+
+impl_channel!(Ain0, Ain0Avss);
+impl_channel!(Ain1, Ain1Avss);
+impl_channel!(Ain2, Ain2Avss);
+impl_channel!(Ain3, Ain3Avss);
+
+impl<BUS, E, PIN> OneShot<ADS122x04<BUS>, i32, PIN> for ADS122x04<BUS>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+        PIN: Channel<ADS122x04<BUS>, ID=Mux>,
+{
+    type Error = Error<E>;
+
+    /// Start a conversion on the channel and return `WouldBlock` until the raw ADC value is available
+    fn read(&mut self, _pin: &mut PIN) -> nb::Result<i32, Self::Error> {
+        let mux = PIN::channel();
+        if !self.oneshot_pending || self.config.mux as u8 != mux as u8 {
+            self.set_input_mux(mux)?;
+            self.start()?;
+            self.oneshot_pending = true;
+            return Err(nb::Error::WouldBlock);
+        }
+        if !self.get_data_ready()? {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.oneshot_pending = false;
+        Ok(self.get_raw_adc()?)
+    }
+}
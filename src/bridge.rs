@@ -0,0 +1,247 @@
+//! Wheatstone bridge / load cell measurements
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::scale::{LinearScale, ScaledValue};
+use crate::{Error, ADS122x04};
+
+/// Wheatstone bridge (e.g. load cell) measured ratiometrically.
+/// The bridge excitation has to be connected to REFP/REFN, so the reading is independent of the excitation voltage.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Bridge {
+    /// differential input the bridge output is connected to
+    pub mux: Mux,
+    /// PGA gain
+    pub gain: Gain,
+    /// excitation voltage of the bridge (V), used as external reference
+    pub excitation: f32,
+    /// scaling profile mapping the bridge output (mV/V) after tare and span correction to engineering units
+    pub scale: LinearScale,
+    tare: f32,
+    span: f32,
+}
+
+impl Bridge {
+    /// Create a new bridge with the rated output `sensitivity` (mV/V) at the rated capacity in engineering units,
+    /// without clamping. Returns `None` for a zero sensitivity.
+    pub fn new(mux: Mux, gain: Gain, excitation: f32, sensitivity: f32, capacity: f32) -> Option<Self> {
+        Some(Bridge {
+            mux,
+            gain,
+            excitation,
+            scale: LinearScale::new(0.0, sensitivity, 0.0, capacity)?,
+            tare: 0.0,
+            span: 1.0,
+        })
+    }
+
+    /// Use `scale` to map the bridge output (mV/V) to engineering units instead of the rated output and capacity
+    pub fn with_scale(mut self, scale: LinearScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Clamp the engineering value to the range of the scale, see `LinearScale::with_clamping()`
+    pub fn with_clamping(mut self, clamp: bool) -> Self {
+        self.scale.clamp = clamp;
+        self
+    }
+
+    /// Configure the MUX, gain and the ratiometric reference of the ADC
+    pub fn configure<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        adc.set_input_mux(self.mux)?;
+        adc.set_pga_bypass(false)?;
+        adc.set_gain(self.gain)?;
+        adc.set_vref(VRef::External(self.excitation))
+    }
+
+    /// Take a measurement and return the bridge output in mV/V, without tare
+    pub fn read_mv_per_v<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<f32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let raw = adc.measure()?;
+        Ok(adc.convert_raw_to_ratio(raw) * 1000.0)
+    }
+
+    /// Take a measurement and return the bridge load in engineering units
+    pub fn read<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<ScaledValue, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.read_mv_per_v(adc).map(|mv_per_v| self.convert(mv_per_v))
+    }
+
+    /// Convert a bridge output in mV/V to engineering units, correcting for the tare and the span
+    pub fn convert(&self, mv_per_v: f32) -> ScaledValue {
+        self.scale.apply((mv_per_v - self.tare) * self.span)
+    }
+
+    /// Take a measurement and use it as zero
+    pub fn tare<BUS, E>(&mut self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.tare = self.read_mv_per_v(adc)?;
+        Ok(())
+    }
+
+    /// Calibrate the span with a known load (in engineering units) applied to the bridge
+    pub fn set_span<BUS, E>(&mut self, adc: &mut ADS122x04<BUS>, known_load: f32) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let reading = self.read_mv_per_v(adc)? - self.tare;
+        let scale = self.scale;
+        if reading == 0.0 || scale.output_span == scale.output_zero {
+            return Err(Error::InvalidValue);
+        }
+        // bridge output the scale maps to the known load
+        let expected = scale.input_zero
+            + (known_load - scale.output_zero) / (scale.output_span - scale.output_zero)
+            * (scale.input_span - scale.input_zero);
+        self.span = expected / reading;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Arrangement of the strain gauges in the bridge
+pub enum BridgeConfiguration {
+    /// one active gauge, the other arms are completion resistors
+    QuarterBridge,
+    /// two active gauges in adjacent arms, strained in opposite directions (bending)
+    HalfBridge,
+    /// four active gauges, adjacent gauges strained in opposite directions (bending)
+    FullBridge,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Strain gauge bridge measured ratiometrically, returning the strain in microstrain (µε).
+/// The bridge excitation has to be connected to REFP/REFN, the output is taken as positive for tensile strain.
+pub struct StrainGauge {
+    /// differential input the bridge output is connected to
+    pub mux: Mux,
+    /// PGA gain
+    pub gain: Gain,
+    /// excitation voltage of the bridge (V), used as external reference
+    pub excitation: f32,
+    /// arrangement of the gauges
+    pub configuration: BridgeConfiguration,
+    /// gauge factor of the strain gauges
+    pub gauge_factor: f32,
+    /// nominal resistance of the strain gauge in Ohm
+    pub gauge_resistance: f32,
+    /// resistance of the quarter-bridge completion resistor in the arm adjacent to the gauge in Ohm
+    pub completion_resistance: f32,
+    /// lead resistance in series with the gauge of a quarter bridge in Ohm
+    pub lead_resistance: f32,
+    zero: f32,
+}
+
+impl StrainGauge {
+    /// Create a strain gauge bridge with matched completion resistors and negligible lead resistance
+    pub fn new(
+        mux: Mux,
+        gain: Gain,
+        excitation: f32,
+        configuration: BridgeConfiguration,
+        gauge_factor: f32,
+    ) -> Self {
+        StrainGauge {
+            mux,
+            gain,
+            excitation,
+            configuration,
+            gauge_factor,
+            gauge_resistance: 350.0,
+            completion_resistance: 350.0,
+            lead_resistance: 0.0,
+            zero: 0.0,
+        }
+    }
+
+    /// Compensate the initial unbalance of a quarter bridge caused by a completion resistor not matching the gauge
+    /// and the desensitization by the lead resistance (Ohm)
+    pub fn with_quarter_bridge_completion(
+        mut self,
+        gauge_resistance: f32,
+        completion_resistance: f32,
+        lead_resistance: f32,
+    ) -> Self {
+        self.gauge_resistance = gauge_resistance;
+        self.completion_resistance = completion_resistance;
+        self.lead_resistance = lead_resistance;
+        self
+    }
+
+    /// Configure the MUX, gain and the ratiometric reference of the ADC
+    pub fn configure<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        adc.set_input_mux(self.mux)?;
+        adc.set_pga_bypass(false)?;
+        adc.set_gain(self.gain)?;
+        adc.set_vref(VRef::External(self.excitation))
+    }
+
+    /// Take a measurement and return the bridge output ratio Vout / Vex
+    pub fn read_ratio<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<f32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let raw = adc.measure()?;
+        Ok(adc.convert_raw_to_ratio(raw))
+    }
+
+    /// Take a measurement and return the strain in µε
+    pub fn read_microstrain<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<f32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.read_ratio(adc).map(|ratio| self.convert(ratio))
+    }
+
+    /// Take the current reading of the unstrained bridge as zero
+    pub fn zero<BUS, E>(&mut self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.zero = 0.0;
+        self.zero = self.read_ratio(adc)? - self.completion_unbalance();
+        Ok(())
+    }
+
+    /// Convert the bridge output ratio Vout / Vex to strain in µε
+    pub fn convert(&self, ratio: f32) -> f32 {
+        let vr = ratio - self.zero - self.completion_unbalance();
+        let strain = match self.configuration {
+            BridgeConfiguration::QuarterBridge => {
+                4.0 * vr / (self.gauge_factor * (1.0 + 2.0 * vr))
+                    * (1.0 + self.lead_resistance / self.gauge_resistance)
+            }
+            BridgeConfiguration::HalfBridge => 2.0 * vr / self.gauge_factor,
+            BridgeConfiguration::FullBridge => vr / self.gauge_factor,
+        };
+        strain * 1e6
+    }
+
+    /// output ratio of an unstrained quarter bridge caused by the mismatch of the gauge (including the leads)
+    /// and its completion resistor
+    fn completion_unbalance(&self) -> f32 {
+        match self.configuration {
+            BridgeConfiguration::QuarterBridge => {
+                let gauge = self.gauge_resistance + self.lead_resistance;
+                gauge / (gauge + self.completion_resistance) - 0.5
+            }
+            BridgeConfiguration::HalfBridge | BridgeConfiguration::FullBridge => 0.0,
+        }
+    }
+}
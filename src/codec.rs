@@ -0,0 +1,640 @@
+//! Pure encoding and decoding of the configuration registers and data frames, independent of the bus
+
+#[cfg(feature = "float")]
+use libm::fabsf;
+
+use crate::registers::*;
+use crate::Error;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Configuration of the device as stored in the configuration registers
+pub struct DeviceConfig {
+    /// input multiplexer (MUX)
+    pub mux: Mux,
+    /// PGA gain (GAIN)
+    pub gain: Gain,
+    /// PGA bypassed (PGA_BYPASS)
+    pub pga_bypass: bool,
+    /// data rate and operating mode (DR, MODE)
+    pub data_rate: DataRate,
+    /// conversion mode (CM)
+    pub conversion_mode: ConversionMode,
+    /// voltage reference (VREF)
+    pub v_ref: VRef,
+    /// temperature sensor mode (TS)
+    pub temperature_sensor_mode: bool,
+    /// data counter enabled (DCNT)
+    pub data_counter_enable: bool,
+    /// data integrity check (CRC)
+    pub crc: Crc,
+    /// 10 uA burnout current sources enabled (BCS)
+    pub burn_out_current_sources: bool,
+    /// excitation current level (IDAC)
+    pub current_source: CurrentSource,
+    /// routing of the excitation current source 1 (I1MUX)
+    pub current_route_1: CurrentRoute,
+    /// routing of the excitation current source 2 (I2MUX)
+    pub current_route_2: CurrentRoute,
+}
+
+/// distance (V) the PGA needs to the supply rails to stay linear
+#[cfg(feature = "float")]
+const PGA_OUTPUT_MARGIN: f32 = 0.2;
+
+/// distance (V) the inputs may exceed the supply rails with the PGA bypassed
+#[cfg(feature = "float")]
+const PGA_BYPASS_INPUT_MARGIN: f32 = 0.1;
+
+#[cfg(feature = "float")]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Result of `check_common_mode()`: headroom (V) to the limits of the linear input range, negative if violated
+pub struct CommonModeCheck {
+    /// headroom towards AVSS
+    pub lower_headroom: f32,
+    /// headroom towards AVDD
+    pub upper_headroom: f32,
+}
+
+#[cfg(feature = "float")]
+impl CommonModeCheck {
+    /// The input stage stays in its linear region
+    pub fn is_ok(&self) -> bool {
+        self.lower_headroom >= 0.0 && self.upper_headroom >= 0.0
+    }
+}
+
+impl Default for DeviceConfig {
+    /// Reset configuration of the device
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceConfig {
+    /// Reset configuration of the device, usable in const contexts
+    pub const fn new() -> Self {
+        DeviceConfig {
+            mux: Mux::Ain0Ain1,
+            gain: Gain::Gain1,
+            pga_bypass: false,
+            data_rate: DataRate::Sps20Normal,
+            conversion_mode: ConversionMode::SingleShot,
+            v_ref: VRef::Internal,
+            temperature_sensor_mode: false,
+            data_counter_enable: false,
+            crc: Crc::Disabled,
+            burn_out_current_sources: false,
+            current_source: CurrentSource::Off,
+            current_route_1: CurrentRoute::Off,
+            current_route_2: CurrentRoute::Off,
+        }
+    }
+
+    /// The first conflict between the settings of the configuration, `None` if the configuration is consistent.
+    /// These are the conflicts rejected by the setters of the driver: the PGA can only be bypassed at gains up to 4.
+    pub const fn conflict(&self) -> Option<&'static str> {
+        if self.pga_bypass && (self.gain as u8) > Gain::Gain4 as u8 {
+            return Some("the PGA can only be bypassed at gains 1, 2 and 4");
+        }
+        None
+    }
+
+    /// Check the configuration before it is written, returns `Error::ConfigConflict` for a conflict between its
+    /// settings, see `conflict()`
+    pub fn validate<E>(&self) -> Result<(), Error<E>> {
+        match self.conflict() {
+            Some(reason) => Err(Error::ConfigConflict(reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// The data rate is a turbo mode rate
+    pub fn turbo_mode(&self) -> bool {
+        (self.data_rate as u8 & 0b1) == 1
+    }
+
+    #[cfg(feature = "float")]
+    /// Input voltage (V) of one LSB of the conversion result: VREF / (2^23 * gain)
+    pub fn lsb_voltage(&self) -> f32 {
+        let gain = self.gain.factor() as f32;
+        self.v_ref.to_voltage() / ((1u32 << 23) as f32 * gain)
+    }
+
+    #[cfg(feature = "float")]
+    /// Check whether the input voltages `vin_p` and `vin_n` (V, referred to AVSS) keep the input stage in its
+    /// linear region at the configured gain and supply voltage `avdd` (V), following the datasheet equations:
+    /// with the PGA enabled, AVSS + 0.2 V + Gain * |VIN| / 2 <= VCM <= AVDD - 0.2 V - Gain * |VIN| / 2,
+    /// with the PGA bypassed, AVSS - 0.1 V <= V(AINx) <= AVDD + 0.1 V.
+    pub fn check_common_mode(&self, vin_p: f32, vin_n: f32, avdd: f32) -> CommonModeCheck {
+        if self.pga_bypassed() {
+            return CommonModeCheck {
+                lower_headroom: vin_p.min(vin_n) + PGA_BYPASS_INPUT_MARGIN,
+                upper_headroom: avdd + PGA_BYPASS_INPUT_MARGIN - vin_p.max(vin_n),
+            };
+        }
+        let common_mode = (vin_p + vin_n) / 2.0;
+        let swing = self.gain.factor() as f32 * fabsf(vin_p - vin_n) / 2.0;
+        CommonModeCheck {
+            lower_headroom: common_mode - PGA_OUTPUT_MARGIN - swing,
+            upper_headroom: avdd - PGA_OUTPUT_MARGIN - swing - common_mode,
+        }
+    }
+
+    /// `true` if the PGA is bypassed, which is only possible for the gains 1, 2 and 4
+    pub const fn pga_bypassed(&self) -> bool {
+        self.pga_bypass && (self.gain as u8) <= Gain::Gain4 as u8
+    }
+
+    /// Set the input multiplexer
+    pub fn mux(&mut self, mux: Mux) -> &mut Self {
+        self.mux = mux;
+        self
+    }
+
+    /// Set the PGA gain
+    pub fn gain(&mut self, gain: Gain) -> &mut Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Bypass the PGA
+    pub fn pga_bypass(&mut self, state: bool) -> &mut Self {
+        self.pga_bypass = state;
+        self
+    }
+
+    /// Set the data rate and operating mode
+    pub fn data_rate(&mut self, rate: DataRate) -> &mut Self {
+        self.data_rate = rate;
+        self
+    }
+
+    /// Set the conversion mode
+    pub fn conversion_mode(&mut self, mode: ConversionMode) -> &mut Self {
+        self.conversion_mode = mode;
+        self
+    }
+
+    /// Set the voltage reference
+    pub fn v_ref(&mut self, v_ref: VRef) -> &mut Self {
+        self.v_ref = v_ref;
+        self
+    }
+
+    /// Enable the temperature sensor mode
+    pub fn temperature_sensor_mode(&mut self, state: bool) -> &mut Self {
+        self.temperature_sensor_mode = state;
+        self
+    }
+
+    /// Enable the data counter
+    pub fn data_counter(&mut self, state: bool) -> &mut Self {
+        self.data_counter_enable = state;
+        self
+    }
+
+    /// Set the data integrity check
+    pub fn crc(&mut self, crc: Crc) -> &mut Self {
+        self.crc = crc;
+        self
+    }
+
+    /// Enable the 10 uA burnout current sources
+    pub fn burnout_current_source(&mut self, state: bool) -> &mut Self {
+        self.burn_out_current_sources = state;
+        self
+    }
+
+    /// Set the excitation current level
+    pub fn current_level(&mut self, current: CurrentSource) -> &mut Self {
+        self.current_source = current;
+        self
+    }
+
+    /// Set the routing of the excitation current source 1
+    pub fn current_route_1(&mut self, route: CurrentRoute) -> &mut Self {
+        self.current_route_1 = route;
+        self
+    }
+
+    /// Set the routing of the excitation current source 2
+    pub fn current_route_2(&mut self, route: CurrentRoute) -> &mut Self {
+        self.current_route_2 = route;
+        self
+    }
+
+    /// Register image of the configuration (registers 0 to 3), see `encode_registers()`.
+    /// `DeviceConfig::from_register_bytes(config.to_register_bytes(), config.v_ref.to_voltage())` returns `config`
+    /// for every configuration.
+    pub const fn to_register_bytes(&self) -> [u8; 4] {
+        encode_registers(self)
+    }
+
+    /// Configuration stored in a register image (registers 0 to 3), e.g. read back from the device.
+    /// `v_ref_voltage` is the voltage of an external or supply reference, as it is not stored on the device.
+    ///
+    /// Every image is accepted: reserved field values are mapped like `decode()` does (MUX `1111` to shorted
+    /// inputs, reserved DR/MODE to 20 SPS normal mode, VREF `11` to the internal reference, CRC `11` to disabled,
+    /// I1MUX/I2MUX `111` to off), the read-only DRDY bit and the reserved bits of register 3 are ignored.
+    /// For images without reserved field values `from_register_bytes(bytes, v).to_register_bytes()` returns
+    /// `bytes` with DRDY and the reserved bits cleared.
+    pub fn from_register_bytes(bytes: [u8; 4], v_ref_voltage: f32) -> Self {
+        let mut config = DeviceConfig::new();
+        config.v_ref = VRef::External(v_ref_voltage);
+        for (reg, val) in bytes.iter().enumerate() {
+            config.decode(reg as u8, *val);
+        }
+        config
+    }
+
+    /// Encode the configuration register with the given index (0 to 3)
+    pub fn encode(&self, reg: u8) -> Option<u8> {
+        match reg {
+            0x00 => Some(encode_reg0(self)),
+            0x01 => Some(encode_reg1(self)),
+            0x02 => Some(encode_reg2(self)),
+            0x03 => Some(encode_reg3(self)),
+            _ => None,
+        }
+    }
+
+    /// Update the configuration from the value of the configuration register with the given index (0 to 3).
+    /// The voltage of an external or supply reference is kept, as it is not stored on the device.
+    pub fn decode(&mut self, reg: u8, val: u8) -> Option<()> {
+        match reg {
+            0x00 => {
+                let reg0 = decode_reg0(val);
+                self.mux = reg0.mux;
+                self.gain = reg0.gain;
+                self.pga_bypass = reg0.pga_bypass;
+            }
+            0x01 => {
+                let reg1 = decode_reg1(val);
+                self.data_rate = reg1.data_rate;
+                self.conversion_mode = reg1.conversion_mode;
+                self.v_ref = VRef::from(reg1.v_ref, self.v_ref.to_voltage());
+                self.temperature_sensor_mode = reg1.temperature_sensor_mode;
+            }
+            0x02 => {
+                let reg2 = decode_reg2(val);
+                self.data_counter_enable = reg2.data_counter_enable;
+                self.crc = reg2.crc;
+                self.burn_out_current_sources = reg2.burn_out_current_sources;
+                self.current_source = reg2.current_source;
+            }
+            0x03 => {
+                let reg3 = decode_reg3(val);
+                self.current_route_1 = reg3.current_route_1;
+                self.current_route_2 = reg3.current_route_2;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Fields of configuration register 0
+pub struct Reg0 {
+    /// input multiplexer (MUX)
+    pub mux: Mux,
+    /// PGA gain (GAIN)
+    pub gain: Gain,
+    /// PGA bypassed (PGA_BYPASS)
+    pub pga_bypass: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Fields of configuration register 1
+pub struct Reg1 {
+    /// data rate and operating mode (DR, MODE)
+    pub data_rate: DataRate,
+    /// conversion mode (CM)
+    pub conversion_mode: ConversionMode,
+    /// voltage reference selection bits (VREF), see `VRef::to_val()`
+    pub v_ref: u8,
+    /// temperature sensor mode (TS)
+    pub temperature_sensor_mode: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Fields of configuration register 2
+pub struct Reg2 {
+    /// conversion data ready (DRDY), read-only
+    pub data_ready: bool,
+    /// data counter enabled (DCNT)
+    pub data_counter_enable: bool,
+    /// data integrity check (CRC)
+    pub crc: Crc,
+    /// 10 uA burnout current sources enabled (BCS)
+    pub burn_out_current_sources: bool,
+    /// excitation current level (IDAC)
+    pub current_source: CurrentSource,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Fields of configuration register 3
+pub struct Reg3 {
+    /// routing of the excitation current source 1 (I1MUX)
+    pub current_route_1: CurrentRoute,
+    /// routing of the excitation current source 2 (I2MUX)
+    pub current_route_2: CurrentRoute,
+}
+
+/// Encode configuration register 0: MUX[7:4], GAIN[3:1], PGA_BYPASS[0]
+pub const fn encode_reg0(config: &DeviceConfig) -> u8 {
+    (config.pga_bypass as u8) | ((config.gain as u8) << 1) | ((config.mux as u8) << 4)
+}
+
+/// Encode configuration register 1: DR[7:5], MODE[4], CM[3], VREF[2:1], TS[0]
+pub const fn encode_reg1(config: &DeviceConfig) -> u8 {
+    // the data rate already contains the operating mode in its lowest bit
+    (config.temperature_sensor_mode as u8)
+        | (config.v_ref.to_val() << 1)
+        | ((config.conversion_mode as u8) << 3)
+        | ((config.data_rate as u8) << 4)
+}
+
+/// Encode configuration register 2: DCNT[6], CRC[5:4], BCS[3], IDAC[2:0]
+pub const fn encode_reg2(config: &DeviceConfig) -> u8 {
+    (config.current_source as u8)
+        | ((config.burn_out_current_sources as u8) << 3)
+        | ((config.crc as u8) << 4)
+        | ((config.data_counter_enable as u8) << 6)
+}
+
+/// Encode configuration register 3: I1MUX[7:5], I2MUX[4:2]
+pub const fn encode_reg3(config: &DeviceConfig) -> u8 {
+    ((config.current_route_2 as u8) << 2) | ((config.current_route_1 as u8) << 5)
+}
+
+/// Decode configuration register 0
+pub fn decode_reg0(val: u8) -> Reg0 {
+    Reg0 {
+        mux: Mux::from(val >> 4),
+        gain: Gain::from((val >> 1) & 0b111),
+        pga_bypass: (val & 0b1) == 1,
+    }
+}
+
+/// Decode configuration register 1
+pub fn decode_reg1(val: u8) -> Reg1 {
+    Reg1 {
+        data_rate: DataRate::from((val >> 4) & 0b1111),
+        conversion_mode: ConversionMode::from((val >> 3) & 0b1),
+        v_ref: (val >> 1) & 0b11,
+        temperature_sensor_mode: (val & 0b1) == 1,
+    }
+}
+
+/// Decode configuration register 2
+pub fn decode_reg2(val: u8) -> Reg2 {
+    Reg2 {
+        data_ready: ((val >> 7) & 0b1) == 1,
+        data_counter_enable: ((val >> 6) & 0b1) == 1,
+        crc: Crc::from((val >> 4) & 0b11),
+        burn_out_current_sources: ((val >> 3) & 0b1) == 1,
+        current_source: CurrentSource::from(val & 0b111),
+    }
+}
+
+/// Decode configuration register 3
+pub fn decode_reg3(val: u8) -> Reg3 {
+    Reg3 {
+        current_route_1: CurrentRoute::from((val >> 5) & 0b111),
+        current_route_2: CurrentRoute::from((val >> 2) & 0b111),
+    }
+}
+
+/// Maximum length of a conversion data frame: data counter, 24-bit data and inverted counter and data
+pub const MAX_DATA_FRAME_LEN: usize = 8;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Conversion data frame returned by RDATA
+pub struct DataFrame {
+    /// data counter, if enabled (DCNT)
+    pub counter: Option<u8>,
+    /// raw 24-bit conversion data
+    pub data: u32,
+}
+
+/// Sign-extend the raw 24-bit two's complement conversion data
+pub const fn sign_extend(data: u32) -> i32 {
+    ((data << 8) as i32) >> 8
+}
+
+/// CRC-16-CCITT (polynomial 0x1021, initial value 0xFFFF) as used by the data integrity check
+pub fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0xFFFF, |crc, &byte| {
+        (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+/// Length of the conversion data frame with the given data counter and data integrity settings
+pub fn data_frame_len(counter: bool, crc: Crc) -> usize {
+    let payload = 3 + counter as usize;
+    match crc {
+        Crc::Disabled => payload,
+        Crc::Inverted => 2 * payload,
+        Crc::Crc16 => payload + 2,
+    }
+}
+
+/// Encode a conversion data frame into `buffer`, returns the length of the frame
+pub fn encode_data_frame(frame: &DataFrame, crc: Crc, buffer: &mut [u8; MAX_DATA_FRAME_LEN]) -> usize {
+    let data = frame.data.to_be_bytes();
+    let mut len = 0;
+    if let Some(counter) = frame.counter {
+        buffer[0] = counter;
+        len = 1;
+    }
+    buffer[len..len + 3].copy_from_slice(&data[1..]);
+    let payload = len + 3;
+    match crc {
+        Crc::Disabled => payload,
+        Crc::Inverted => {
+            for i in 0..payload {
+                buffer[payload + i] = !buffer[i];
+            }
+            2 * payload
+        }
+        Crc::Crc16 => {
+            let crc = crc16(&buffer[..payload]).to_be_bytes();
+            buffer[payload..payload + 2].copy_from_slice(&crc);
+            payload + 2
+        }
+    }
+}
+
+/// Decode a conversion data frame with the given data counter and data integrity settings.
+/// Returns `Error::CrcMismatch` if the data integrity check fails and `Error::InvalidValue` if the frame
+/// has the wrong length.
+pub fn decode_data_frame<E>(frame: &[u8], counter: bool, crc: Crc) -> Result<DataFrame, Error<E>> {
+    if frame.len() != data_frame_len(counter, crc) {
+        return Err(Error::InvalidValue);
+    }
+    let payload = 3 + counter as usize;
+    let (payload, check) = frame.split_at(payload);
+    match crc {
+        Crc::Disabled => {}
+        Crc::Inverted => {
+            // compare the last two bytes of the data with the inverted copy
+            let expected = u16::from_be_bytes([!payload[payload.len() - 2], !payload[payload.len() - 1]]);
+            let got = u16::from_be_bytes([check[check.len() - 2], check[check.len() - 1]]);
+            if payload.iter().zip(check).any(|(byte, inverted)| *byte != !*inverted) {
+                return Err(Error::CrcMismatch { expected, got });
+            }
+        }
+        Crc::Crc16 => {
+            let expected = crc16(payload);
+            let got = u16::from_be_bytes([check[0], check[1]]);
+            if expected != got {
+                return Err(Error::CrcMismatch { expected, got });
+            }
+        }
+    }
+    let (counter, data) = match counter {
+        true => (Some(payload[0]), &payload[1..]),
+        false => (None, payload),
+    };
+    Ok(DataFrame {
+        counter,
+        data: (data[0] as u32) << 16 | (data[1] as u32) << 8 | (data[2] as u32),
+    })
+}
+
+/// Encode the configuration registers 0 to 3
+pub const fn encode_registers(config: &DeviceConfig) -> [u8; 4] {
+    [encode_reg0(config), encode_reg1(config), encode_reg2(config), encode_reg3(config)]
+}
+
+/// Fixed configuration known at compile time, applied with `ADS122x04::apply_profile()`.
+/// The register image is evaluated at compile time, e.g.
+///
+/// ```ignore
+/// struct Thermocouple;
+///
+/// impl ConfigProfile for Thermocouple {
+///     const CONFIG: DeviceConfig = DeviceConfig {
+///         mux: Mux::Ain0Ain1,
+///         gain: Gain::Gain32,
+///         ..DeviceConfig::new()
+///     };
+/// }
+/// ```
+pub trait ConfigProfile {
+    /// configuration of the profile
+    const CONFIG: DeviceConfig;
+    /// register image of the configuration
+    const REGISTERS: [u8; 4] = encode_registers(&Self::CONFIG);
+    /// conversions discarded after a configuration change that needs settling, see
+    /// `ADS122x04::set_settling_discards()`
+    const SETTLING_DISCARDS: u8 = 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_extension() {
+        assert_eq!(sign_extend(0x000000), 0);
+        assert_eq!(sign_extend(0x000001), 1);
+        assert_eq!(sign_extend(0x7FFFFF), 8_388_607);
+        assert_eq!(sign_extend(0x800000), -8_388_608);
+        assert_eq!(sign_extend(0xFFFFFF), -1);
+        let frame = decode_data_frame::<()>(&[0xFF, 0xFF, 0xFE], false, Crc::Disabled).unwrap();
+        assert_eq!(sign_extend(frame.data), -2);
+    }
+
+    #[test]
+    fn data_frame_counter() {
+        let frame = DataFrame {
+            counter: Some(0xA5),
+            data: 0x123456,
+        };
+        for crc in [Crc::Disabled, Crc::Inverted, Crc::Crc16] {
+            let mut buffer = [0; MAX_DATA_FRAME_LEN];
+            let len = encode_data_frame(&frame, crc, &mut buffer);
+            assert_eq!(len, data_frame_len(true, crc));
+            assert_eq!(buffer[..4], [0xA5, 0x12, 0x34, 0x56]);
+            assert_eq!(decode_data_frame::<()>(&buffer[..len], true, crc).unwrap(), frame);
+            assert!(matches!(
+                decode_data_frame::<()>(&buffer[..len], false, crc),
+                Err(Error::InvalidValue)
+            ));
+        }
+        let decoded = decode_data_frame::<()>(&[0x12, 0x34, 0x56], false, Crc::Disabled).unwrap();
+        assert_eq!(decoded.counter, None);
+        assert_eq!(decoded.data, 0x123456);
+    }
+
+    #[test]
+    fn data_frame_crc16() {
+        // check value of CRC-16-CCITT with initial value 0xFFFF
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+        let frame = [0x12, 0x34, 0x56, 0x00, 0x00];
+        let crc = crc16(&frame[..3]).to_be_bytes();
+        let frame = [frame[0], frame[1], frame[2], crc[0], crc[1]];
+        assert_eq!(decode_data_frame::<()>(&frame, false, Crc::Crc16).unwrap().data, 0x123456);
+        let corrupted = [frame[0] ^ 0x01, frame[1], frame[2], frame[3], frame[4]];
+        assert!(matches!(
+            decode_data_frame::<()>(&corrupted, false, Crc::Crc16),
+            Err(Error::CrcMismatch { got, .. }) if got == u16::from_be_bytes(crc)
+        ));
+        let inverted = [0x12, 0x34, 0x56, !0x12, !0x34, 0x00];
+        assert!(matches!(
+            decode_data_frame::<()>(&inverted, false, Crc::Inverted),
+            Err(Error::CrcMismatch { .. })
+        ));
+    }
+
+    /// expected register value after a round trip: DRDY and the reserved bits cleared, `None` for reserved
+    /// field values, which are mapped to a valid setting
+    fn canonical(reg: usize, val: u8) -> Option<u8> {
+        let reserved = match reg {
+            0 => val >> 4 == 0b1111,
+            1 => val >> 5 == 0b111 || (val >> 1) & 0b11 == 0b11,
+            2 => (val >> 4) & 0b11 == 0b11,
+            _ => val >> 5 == 0b111 || (val >> 2) & 0b111 == 0b111,
+        };
+        let mask = [0xFF, 0xFF, 0x7F, 0xFC][reg];
+        (!reserved).then_some(val & mask)
+    }
+
+    #[test]
+    fn register_bytes_round_trip() {
+        for reg in 0..4 {
+            for val in 0..=u8::MAX {
+                let mut bytes = DeviceConfig::new().to_register_bytes();
+                bytes[reg] = val;
+                let config = DeviceConfig::from_register_bytes(bytes, 2.5);
+                let encoded = config.to_register_bytes();
+                assert_eq!(
+                    DeviceConfig::from_register_bytes(encoded, 2.5),
+                    config,
+                    "register {} value {:#04x}",
+                    reg,
+                    val
+                );
+                if let Some(expected) = canonical(reg, val) {
+                    bytes[reg] = expected;
+                    assert_eq!(encoded, bytes, "register {} value {:#04x}", reg, val);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Adapters for HALs implementing embedded-hal 0.2, available with the `embedded-hal-02` feature
+//!
+//! The driver is built on embedded-hal 1.0 and embedded-io. Wrap a 0.2 I2C, serial or delay implementation
+//! in the matching adapter to pass it to the driver:
+//!
+//! ```ignore
+//! let mut adc = ADS122x04::new_i2c(0x40, I2cCompat::new(i2c));
+//! adc.reset(&mut DelayCompat::new(delay))?;
+//! ```
+
+use core::fmt::Debug;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{self, I2c, Operation, SevenBitAddress};
+use embedded_hal_02::blocking::delay::DelayUs;
+use embedded_hal_02::blocking::i2c as i2c_02;
+use embedded_hal_02::serial as serial_02;
+
+/// Error of a wrapped embedded-hal 0.2 implementation
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CompatError<E>(pub E);
+
+impl<E: Debug> i2c::Error for CompatError<E> {
+    fn kind(&self) -> i2c::ErrorKind {
+        i2c::ErrorKind::Other
+    }
+}
+
+impl<E: Debug> embedded_io::Error for CompatError<E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        embedded_io::ErrorKind::Other
+    }
+}
+
+/// embedded-hal 1.0 `I2c` implementation for an embedded-hal 0.2 I2C handler
+#[derive(Debug)]
+pub struct I2cCompat<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C> I2cCompat<I2C> {
+    /// Wrap an embedded-hal 0.2 I2C handler
+    pub fn new(i2c: I2C) -> Self {
+        I2cCompat { i2c }
+    }
+
+    /// Return the wrapped I2C handler
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C, E> i2c::ErrorType for I2cCompat<I2C>
+    where
+        I2C: i2c_02::Write<Error=E> + i2c_02::WriteRead<Error=E> + i2c_02::Read<Error=E>,
+        E: Debug,
+{
+    type Error = CompatError<E>;
+}
+
+impl<I2C, E> I2c<SevenBitAddress> for I2cCompat<I2C>
+    where
+        I2C: i2c_02::Write<Error=E> + i2c_02::WriteRead<Error=E> + i2c_02::Read<Error=E>,
+        E: Debug,
+{
+    /// Executes the operations one by one, a write directly followed by a read is combined into a single
+    /// `WriteRead` with a repeated start
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        let mut operations = operations.iter_mut().peekable();
+        while let Some(operation) = operations.next() {
+            match operation {
+                Operation::Write(bytes) => {
+                    if let Some(Operation::Read(buffer)) = operations.peek_mut() {
+                        self.i2c.write_read(address, bytes, buffer).map_err(CompatError)?;
+                        operations.next();
+                    } else {
+                        self.i2c.write(address, bytes).map_err(CompatError)?;
+                    }
+                }
+                Operation::Read(buffer) => self.i2c.read(address, buffer).map_err(CompatError)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// embedded-io `Read`/`Write`/`ReadReady` implementation for an embedded-hal 0.2 serial handler
+#[derive(Debug)]
+pub struct SerialCompat<UART> {
+    serial: UART,
+    /// byte received while checking `read_ready()`
+    peeked: Option<u8>,
+}
+
+impl<UART> SerialCompat<UART> {
+    /// Wrap an embedded-hal 0.2 serial handler
+    pub fn new(serial: UART) -> Self {
+        SerialCompat { serial, peeked: None }
+    }
+
+    /// Return the wrapped serial handler
+    pub fn release(self) -> UART {
+        self.serial
+    }
+}
+
+impl<UART, E> embedded_io::ErrorType for SerialCompat<UART>
+    where
+        UART: serial_02::Write<u8, Error=E> + serial_02::Read<u8, Error=E>,
+        E: Debug,
+{
+    type Error = CompatError<E>;
+}
+
+impl<UART, E> embedded_io::Read for SerialCompat<UART>
+    where
+        UART: serial_02::Write<u8, Error=E> + serial_02::Read<u8, Error=E>,
+        E: Debug,
+{
+    /// Blocks until at least one byte is received, then returns the bytes available without blocking
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = match self.peeked.take() {
+            Some(byte) => byte,
+            None => nb::block!(self.serial.read()).map_err(CompatError)?,
+        };
+        let mut count = 1;
+        while count < buf.len() {
+            match self.serial.read() {
+                Ok(byte) => buf[count] = byte,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(CompatError(e)),
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl<UART, E> embedded_io::ReadReady for SerialCompat<UART>
+    where
+        UART: serial_02::Write<u8, Error=E> + serial_02::Read<u8, Error=E>,
+        E: Debug,
+{
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        if self.peeked.is_none() {
+            match self.serial.read() {
+                Ok(byte) => self.peeked = Some(byte),
+                Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(e)) => return Err(CompatError(e)),
+            }
+        }
+        Ok(self.peeked.is_some())
+    }
+}
+
+impl<UART, E> embedded_io::Write for SerialCompat<UART>
+    where
+        UART: serial_02::Write<u8, Error=E> + serial_02::Read<u8, Error=E>,
+        E: Debug,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        for byte in buf {
+            nb::block!(self.serial.write(*byte)).map_err(CompatError)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.serial.flush()).map_err(CompatError)
+    }
+}
+
+/// embedded-hal 1.0 `DelayNs` implementation for an embedded-hal 0.2 `DelayUs<u32>` provider
+#[derive(Debug)]
+pub struct DelayCompat<D> {
+    delay: D,
+}
+
+impl<D> DelayCompat<D> {
+    /// Wrap an embedded-hal 0.2 delay provider
+    pub fn new(delay: D) -> Self {
+        DelayCompat { delay }
+    }
+
+    /// Return the wrapped delay provider
+    pub fn release(self) -> D {
+        self.delay
+    }
+}
+
+impl<D: DelayUs<u32>> DelayNs for DelayCompat<D> {
+    /// Delays are rounded up to whole microseconds
+    fn delay_ns(&mut self, ns: u32) {
+        self.delay.delay_us(ns.div_ceil(1000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.delay.delay_us(us);
+    }
+}
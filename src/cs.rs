@@ -0,0 +1,55 @@
+//! Device shared between the main context and interrupt handlers, protected by a critical section
+//!
+//! ```ignore
+//! static ADC: CsDevice<I2cInterface<I2c>> = CsDevice::new();
+//!
+//! fn main() {
+//!     ADC.init(ADS122x04::new_i2c(0x40, i2c));
+//!     ADC.lock(|adc| adc.start_continuous().map(|_| ()));
+//! }
+//!
+//! #[interrupt]
+//! fn EXTI0() {
+//!     let result = ADC.lock(|adc| adc.read_conversion());
+//! }
+//! ```
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::ADS122x04;
+
+/// Device that can be placed in a `static` and accessed from any context, every access runs in a critical section
+pub struct CsDevice<BUS> {
+    adc: Mutex<RefCell<Option<ADS122x04<BUS>>>>,
+}
+
+impl<BUS> CsDevice<BUS> {
+    /// Create an empty wrapper, usable in `static` initializers
+    pub const fn new() -> Self {
+        CsDevice { adc: Mutex::new(RefCell::new(None)) }
+    }
+
+    /// Store the device, returning the previously stored one
+    pub fn init(&self, adc: ADS122x04<BUS>) -> Option<ADS122x04<BUS>> {
+        critical_section::with(|cs| self.adc.borrow_ref_mut(cs).replace(adc))
+    }
+
+    /// Remove the device
+    pub fn take(&self) -> Option<ADS122x04<BUS>> {
+        critical_section::with(|cs| self.adc.borrow_ref_mut(cs).take())
+    }
+
+    /// Run `f` with exclusive access to the device in a critical section.
+    /// Returns `None` if no device is stored.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut ADS122x04<BUS>) -> R) -> Option<R> {
+        critical_section::with(|cs| self.adc.borrow_ref_mut(cs).as_mut().map(f))
+    }
+}
+
+impl<BUS> Default for CsDevice<BUS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
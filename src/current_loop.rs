@@ -0,0 +1,120 @@
+//! 4–20 mA current loop inputs measured across a sense resistor
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::scale::{LinearScale, ScaledValue};
+use crate::{Error, ADS122x04};
+
+/// loop currents below this value (mA) indicate a broken loop
+const UNDER_RANGE_MA: f32 = 3.6;
+/// loop currents above this value (mA) are over range
+const OVER_RANGE_MA: f32 = 21.0;
+/// loop current at the zero point of the range (mA)
+const ZERO_MA: f32 = 4.0;
+/// loop current at the span point of the range (mA)
+const SPAN_MA: f32 = 20.0;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Status of the current loop
+pub enum LoopStatus {
+    /// The loop current is within range
+    Ok,
+    /// The loop current is below 3.6 mA (broken loop or sensor failure)
+    UnderRange,
+    /// The loop current is above 21 mA
+    OverRange,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Reading of a current loop input
+pub struct LoopReading {
+    /// loop current in mA
+    pub milliamps: f32,
+    /// loop current mapped to the engineering range, with its position relative to 4–20 mA
+    pub value: ScaledValue,
+    /// status of the loop
+    pub status: LoopStatus,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// 4–20 mA current loop input across a sense resistor
+pub struct CurrentLoop {
+    /// input the sense resistor is connected to
+    pub mux: Mux,
+    /// sense resistor in Ohm
+    pub sense_resistor: f32,
+    /// scaling profile mapping the loop current (mA) to engineering units
+    pub scale: LinearScale,
+}
+
+impl CurrentLoop {
+    /// Create a new current loop input with the engineering range `range_min..range_max` mapped to 4–20 mA,
+    /// without clamping
+    pub fn new(mux: Mux, sense_resistor: f32, range_min: f32, range_max: f32) -> Self {
+        CurrentLoop {
+            mux,
+            sense_resistor,
+            scale: LinearScale {
+                input_zero: ZERO_MA,
+                input_span: SPAN_MA,
+                output_zero: range_min,
+                output_span: range_max,
+                clamp: false,
+            },
+        }
+    }
+
+    /// Use `scale` to map the loop current (mA) to engineering units instead of the linear 4–20 mA range
+    pub fn with_scale(mut self, scale: LinearScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Clamp the engineering value to the range, see `LinearScale::with_clamping()`
+    pub fn with_clamping(mut self, clamp: bool) -> Self {
+        self.scale.clamp = clamp;
+        self
+    }
+
+    /// Configure the input of the ADC at gain 1. For a low-side sense resistor measured single-ended
+    /// (e.g. `Mux::Ain0Avss`), the PGA is bypassed, as its input common-mode range ends 0.2 V above AVSS and
+    /// would distort the readings near 0 mA used for the broken-loop detection.
+    pub fn configure<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        adc.set_input_mux(self.mux)?;
+        adc.set_gain(Gain::Gain1)?;
+        adc.set_pga_bypass(matches!(self.mux.inputs(), Some((_, AnalogInput::Avss))))
+    }
+
+    /// Take a measurement and convert it to the loop current and engineering value
+    pub fn read<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<LoopReading, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let raw = adc.measure()?;
+        let voltage = adc.convert_raw_to_voltage(raw);
+        Ok(self.convert(voltage / self.sense_resistor * 1000.0))
+    }
+
+    /// Convert a loop current (mA) to a reading
+    pub fn convert(&self, milliamps: f32) -> LoopReading {
+        let status = if milliamps < UNDER_RANGE_MA {
+            LoopStatus::UnderRange
+        } else if milliamps > OVER_RANGE_MA {
+            LoopStatus::OverRange
+        } else {
+            LoopStatus::Ok
+        };
+        let value = self.scale.apply(milliamps);
+        LoopReading {
+            milliamps,
+            value,
+            status,
+        }
+    }
+}
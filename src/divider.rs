@@ -0,0 +1,77 @@
+//! Voltage measurements through a resistive divider, e.g. battery monitoring
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{Error, ADS122x04};
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Single-ended input measuring a source voltage through a resistive divider
+pub struct DividerInput {
+    /// single-ended input the divider output is connected to (e.g. `Mux::Ain3Avss`)
+    pub mux: Mux,
+    /// divider ratio Vout / Vin
+    pub ratio: f32,
+    /// output resistance of the divider in Ohm (R1 || R2), used for the loading correction
+    pub source_resistance: f32,
+    /// input resistance of the ADC input in Ohm loading the divider, `None` to neglect the loading
+    pub input_resistance: Option<f32>,
+}
+
+impl DividerInput {
+    /// Create a divider input from the divider ratio Vout / Vin
+    pub fn new(mux: Mux, ratio: f32) -> Self {
+        DividerInput {
+            mux,
+            ratio,
+            source_resistance: 0.0,
+            input_resistance: None,
+        }
+    }
+
+    /// Create a divider input from the resistor between the source and the input (`r_top`)
+    /// and the resistor between the input and AVSS (`r_bottom`) in Ohm
+    pub fn from_resistors(mux: Mux, r_top: f32, r_bottom: f32) -> Self {
+        DividerInput {
+            mux,
+            ratio: r_bottom / (r_top + r_bottom),
+            source_resistance: r_top * r_bottom / (r_top + r_bottom),
+            input_resistance: None,
+        }
+    }
+
+    /// Correct for the input resistance (Ohm) of the ADC loading the divider,
+    /// only effective for dividers created with `from_resistors()`
+    pub fn with_input_resistance(mut self, input_resistance: f32) -> Self {
+        self.input_resistance = Some(input_resistance);
+        self
+    }
+
+    /// Configure the single-ended input with the PGA bypassed, so the input range extends down to AVSS
+    pub fn configure<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        adc.set_input_mux(self.mux)?;
+        adc.set_gain(Gain::Gain1)?;
+        adc.set_pga_bypass(true)
+    }
+
+    /// Take a measurement and rescale it to the source voltage (V)
+    pub fn read<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<f32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let raw = adc.measure()?;
+        Ok(self.convert(adc.convert_raw_to_voltage(raw)))
+    }
+
+    /// Rescale the measured divider output voltage (V) to the source voltage (V)
+    pub fn convert(&self, volts: f32) -> f32 {
+        let unloaded = match self.input_resistance {
+            Some(input_resistance) => volts * (input_resistance + self.source_resistance) / input_resistance,
+            None => volts,
+        };
+        unloaded / self.ratio
+    }
+}
@@ -0,0 +1,210 @@
+//! Filters and estimators for conversion results
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Estimator used to combine a window of samples into a single value
+pub enum Estimator {
+    /// arithmetic mean
+    Mean,
+    /// median, rejects single-sample spikes
+    Median,
+    /// mean after discarding the given number of smallest and largest samples
+    TrimmedMean(usize),
+}
+
+impl Estimator {
+    /// Combine the samples into a single value. The samples are sorted in place.
+    pub fn estimate(&self, samples: &mut [i32]) -> i32 {
+        match *self {
+            Estimator::Mean => trimmed_mean(samples, 0),
+            Estimator::Median => median(samples),
+            Estimator::TrimmedMean(trim) => trimmed_mean(samples, trim),
+        }
+    }
+}
+
+/// Median of the samples, the samples are sorted in place. Returns 0 for an empty slice.
+pub fn median(samples: &mut [i32]) -> i32 {
+    if samples.is_empty() {
+        return 0;
+    }
+    samples.sort_unstable();
+    let mid = samples.len() / 2;
+    if samples.len().is_multiple_of(2) {
+        ((samples[mid - 1] as i64 + samples[mid] as i64) / 2) as i32
+    } else {
+        samples[mid]
+    }
+}
+
+/// Mean of the samples after discarding `trim` samples at both ends, the samples are sorted in place.
+/// At least one sample is kept. Returns 0 for an empty slice.
+pub fn trimmed_mean(samples: &mut [i32], trim: usize) -> i32 {
+    if samples.is_empty() {
+        return 0;
+    }
+    samples.sort_unstable();
+    let trim = trim.min((samples.len() - 1) / 2);
+    let kept = &samples[trim..samples.len() - trim];
+    let sum: i64 = kept.iter().map(|&x| x as i64).sum();
+    (sum / kept.len() as i64) as i32
+}
+
+/// Streaming filter fed with one conversion at a time
+pub trait Filter {
+    /// Feed a new sample and return the filtered value
+    fn update(&mut self, sample: i32) -> i32;
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Pass-through filter
+pub struct NoFilter;
+
+impl Filter for NoFilter {
+    fn update(&mut self, sample: i32) -> i32 {
+        sample
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Moving average over the last `N` samples
+pub struct MovingAverage<const N: usize> {
+    window: [i32; N],
+    index: usize,
+    count: usize,
+    sum: i64,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    /// Create an empty moving average
+    pub fn new() -> Self {
+        const { assert!(N > 0, "the window needs at least one sample") };
+        MovingAverage {
+            window: [0; N],
+            index: 0,
+            count: 0,
+            sum: 0,
+        }
+    }
+
+    /// Current average, `None` if no sample has been fed yet
+    pub fn value(&self) -> Option<i32> {
+        if self.count == 0 {
+            None
+        } else {
+            Some((self.sum / self.count as i64) as i32)
+        }
+    }
+
+    /// Clear the window
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Filter for MovingAverage<N> {
+    fn update(&mut self, sample: i32) -> i32 {
+        if self.count == N {
+            self.sum -= self.window[self.index] as i64;
+        } else {
+            self.count += 1;
+        }
+        self.window[self.index] = sample;
+        self.sum += sample as i64;
+        self.index = (self.index + 1) % N;
+        (self.sum / self.count as i64) as i32
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// First-order IIR low-pass (exponential smoothing) filter: y += alpha * (x - y), with integer math only
+pub struct LowPass {
+    alpha: u32,
+    /// filter state in 16.16 fixed point
+    state: Option<i64>,
+}
+
+impl LowPass {
+    /// Create a new low-pass filter with the smoothing factor `alpha` in units of 1/65536 (65536 = no smoothing).
+    /// The first sample initializes the filter state.
+    pub fn new(alpha: u32) -> Self {
+        LowPass {
+            alpha: alpha.min(1 << 16),
+            state: None,
+        }
+    }
+
+    /// Clear the filter state
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+impl Filter for LowPass {
+    fn update(&mut self, sample: i32) -> i32 {
+        let x = (sample as i64) << 16;
+        let y = match self.state {
+            Some(y) => y + (((x - y) * self.alpha as i64) >> 16),
+            None => x,
+        };
+        self.state = Some(y);
+        (y >> 16) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_rejects_spikes() {
+        assert_eq!(median(&mut []), 0);
+        assert_eq!(median(&mut [7]), 7);
+        assert_eq!(median(&mut [3, 1_000_000, 1, 2, 4]), 3);
+        assert_eq!(median(&mut [4, 1, 3, 2]), 2);
+        assert_eq!(median(&mut [i32::MAX, i32::MAX]), i32::MAX);
+    }
+
+    #[test]
+    fn trimmed_mean_discards_both_ends() {
+        assert_eq!(trimmed_mean(&mut [], 1), 0);
+        assert_eq!(trimmed_mean(&mut [10, 20, 30, 40], 0), 25);
+        assert_eq!(trimmed_mean(&mut [-1_000_000, 10, 20, 30, 1_000_000], 1), 20);
+        // at least one sample is kept
+        assert_eq!(trimmed_mean(&mut [5, 1, 9], 5), 5);
+        assert_eq!(Estimator::TrimmedMean(1).estimate(&mut [100, 0, 2, 4, 6]), 4);
+        assert_eq!(Estimator::Mean.estimate(&mut [1, 2, 3, 6]), 3);
+    }
+
+    #[test]
+    fn moving_average_window() {
+        let mut average = MovingAverage::<4>::new();
+        assert_eq!(average.value(), None);
+        let outputs = [4, 8, 12, 16, 20].map(|sample| average.update(sample));
+        assert_eq!(outputs, [4, 6, 8, 10, 14]);
+    }
+
+    #[test]
+    fn low_pass_step_response() {
+        // alpha = 1/2: the output moves half way to the input with every sample
+        let mut low_pass = LowPass::new(1 << 15);
+        assert_eq!(low_pass.update(0), 0);
+        let outputs = [1000; 4].map(|sample| low_pass.update(sample));
+        assert_eq!(outputs, [500, 750, 875, 937]);
+        let settled = (0..40).map(|_| low_pass.update(1000)).last().unwrap();
+        assert!((999..=1000).contains(&settled));
+
+        let mut pass_through = LowPass::new(u32::MAX);
+        assert_eq!(pass_through.update(-5), -5);
+        assert_eq!(pass_through.update(123), 123);
+    }
+}
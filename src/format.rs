@@ -0,0 +1,197 @@
+//! Rendering of conversion results and records as CSV lines or aligned text, e.g. to dump measurements over a
+//! debug UART during bring-up
+//!
+//! ```ignore
+//! let mut out = SampleWriter::new(uart, SampleFormat::Text);
+//! out.write_header::<ScanResult<4>>()?;
+//! out.write_record(&scanner.scan(&mut adc)?)?;
+//! ```
+
+use core::fmt::{self, Display, Write};
+
+#[cfg(feature = "float")]
+use crate::logger::LogRecord;
+#[cfg(feature = "heapless")]
+use crate::recorder::Record;
+#[cfg(feature = "float")]
+use crate::scan::ScanResult;
+use crate::ConversionResult;
+
+/// width of a column of the aligned text format
+const COLUMN_WIDTH: usize = 12;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Layout of the rendered records
+pub enum SampleFormat {
+    /// comma separated values
+    Csv,
+    /// right-aligned columns separated by spaces
+    Text,
+}
+
+/// Writes the fields of a single line in the selected format
+pub struct Fields<'a> {
+    writer: &'a mut dyn Write,
+    format: SampleFormat,
+    first: bool,
+}
+
+/// counts the characters written, to align a value before writing it
+struct Counter(usize);
+
+impl Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+impl<'a> Fields<'a> {
+    /// Write the fields of a line to `writer`
+    pub fn new(writer: &'a mut dyn Write, format: SampleFormat) -> Self {
+        Fields { writer, format, first: true }
+    }
+
+    /// Write a field
+    pub fn field(&mut self, value: &dyn Display) -> fmt::Result {
+        match self.format {
+            SampleFormat::Csv => {
+                if !self.first {
+                    self.writer.write_char(',')?;
+                }
+            }
+            SampleFormat::Text => {
+                if !self.first {
+                    self.writer.write_char(' ')?;
+                }
+                let mut counter = Counter(0);
+                write!(counter, "{}", value)?;
+                for _ in counter.0..COLUMN_WIDTH {
+                    self.writer.write_char(' ')?;
+                }
+            }
+        }
+        self.first = false;
+        write!(self.writer, "{}", value)
+    }
+
+    /// Write an optional field, `None` is written as an empty field in CSV and as `-` in text
+    pub fn optional(&mut self, value: Option<&dyn Display>) -> fmt::Result {
+        match (value, self.format) {
+            (Some(value), _) => self.field(value),
+            (None, SampleFormat::Csv) => self.field(&""),
+            (None, SampleFormat::Text) => self.field(&"-"),
+        }
+    }
+}
+
+/// Record that can be rendered as a line of fields
+pub trait FormatRecord {
+    /// Write the column names
+    fn write_header(fields: &mut Fields<'_>) -> fmt::Result;
+    /// Write the values of the record, in the order of the column names
+    fn write_fields(&self, fields: &mut Fields<'_>) -> fmt::Result;
+}
+
+impl FormatRecord for ConversionResult {
+    fn write_header(fields: &mut Fields<'_>) -> fmt::Result {
+        fields.field(&"raw")?;
+        fields.field(&"counter")?;
+        fields.field(&"timestamp")?;
+        fields.field(&"saturated")
+    }
+
+    fn write_fields(&self, fields: &mut Fields<'_>) -> fmt::Result {
+        fields.field(&self.raw)?;
+        fields.optional(self.counter.as_ref().map(|counter| counter as &dyn Display))?;
+        fields.optional(self.timestamp.as_ref().map(|timestamp| timestamp as &dyn Display))?;
+        fields.field(&(self.is_saturated() as u8))
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl FormatRecord for Record {
+    fn write_header(fields: &mut Fields<'_>) -> fmt::Result {
+        fields.field(&"time")?;
+        ConversionResult::write_header(fields)
+    }
+
+    fn write_fields(&self, fields: &mut Fields<'_>) -> fmt::Result {
+        fields.field(&self.timestamp)?;
+        self.result.write_fields(fields)
+    }
+}
+
+#[cfg(feature = "float")]
+impl FormatRecord for LogRecord {
+    fn write_header(fields: &mut Fields<'_>) -> fmt::Result {
+        fields.field(&"channel")?;
+        fields.field(&"timestamp")?;
+        fields.field(&"value")?;
+        fields.field(&"raw")
+    }
+
+    fn write_fields(&self, fields: &mut Fields<'_>) -> fmt::Result {
+        fields.field(&self.channel)?;
+        fields.field(&self.timestamp)?;
+        fields.field(&self.value)?;
+        fields.optional(self.result.as_ref().map(|result| &result.raw as &dyn Display))
+    }
+}
+
+#[cfg(feature = "float")]
+impl<const N: usize> FormatRecord for ScanResult<N> {
+    /// `timestamp`, `raw0`..`rawN`, `value0`..`valueN`, `temperature`
+    fn write_header(fields: &mut Fields<'_>) -> fmt::Result {
+        fields.field(&"timestamp")?;
+        for i in 0..N {
+            fields.field(&format_args!("raw{}", i))?;
+        }
+        for i in 0..N {
+            fields.field(&format_args!("value{}", i))?;
+        }
+        fields.field(&"temperature")
+    }
+
+    fn write_fields(&self, fields: &mut Fields<'_>) -> fmt::Result {
+        fields.optional(self.timestamp.as_ref().map(|timestamp| timestamp as &dyn Display))?;
+        for value in self.values.iter() {
+            fields.field(value)?;
+        }
+        for value in self.converted.iter() {
+            fields.field(value)?;
+        }
+        fields.optional(self.temperature.as_ref().map(|temperature| temperature as &dyn Display))
+    }
+}
+
+/// Writes records line by line to a `core::fmt::Write`
+pub struct SampleWriter<W> {
+    writer: W,
+    format: SampleFormat,
+}
+
+impl<W: Write> SampleWriter<W> {
+    /// Write the records to `writer` in the given format
+    pub fn new(writer: W, format: SampleFormat) -> Self {
+        SampleWriter { writer, format }
+    }
+
+    /// Destroy the writer and return the underlying `core::fmt::Write`
+    pub fn release(self) -> W {
+        self.writer
+    }
+
+    /// Write the line with the column names of `R`
+    pub fn write_header<R: FormatRecord>(&mut self) -> fmt::Result {
+        R::write_header(&mut Fields::new(&mut self.writer, self.format))?;
+        self.writer.write_str("\r\n")
+    }
+
+    /// Write a record as a single line
+    pub fn write_record<R: FormatRecord>(&mut self, record: &R) -> fmt::Result {
+        record.write_fields(&mut Fields::new(&mut self.writer, self.format))?;
+        self.writer.write_str("\r\n")
+    }
+}
@@ -0,0 +1,78 @@
+//! Encoding of command frames and decoding of responses into caller-provided buffers
+//!
+//! For applications that run the bus transfers themselves (e.g. with DMA) but want to reuse the protocol logic
+//! of the driver. The UART frames start with the synchronization word, the I2C frames are the bytes written
+//! after the address. Conversion data frames are decoded with `decode_data_frame()`.
+
+use crate::registers::*;
+
+pub use crate::codec::{data_frame_len, decode_data_frame, DataFrame, MAX_DATA_FRAME_LEN};
+
+/// synchronization word preceding every command on the UART interface
+pub const SYNC_WORD: u8 = 0x55;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Transport the frames are encoded for
+pub enum Transport {
+    /// ADS122C04
+    I2c,
+    /// ADS122U04, the frames start with the synchronization word
+    Uart,
+}
+
+/// RREG command byte for the register
+pub const fn rreg_command(register: u8) -> u8 {
+    Commands::RReg as u8 | (register << 2)
+}
+
+/// WREG command byte for the register
+pub const fn wreg_command(register: u8) -> u8 {
+    Commands::WReg as u8 | (register << 2)
+}
+
+/// encode the bytes of a frame, returns `None` if the buffer is too small
+fn encode(transport: Transport, bytes: &[u8], buffer: &mut [u8]) -> Option<usize> {
+    let start = match transport {
+        Transport::I2c => 0,
+        Transport::Uart => 1,
+    };
+    let len = start + bytes.len();
+    let frame = buffer.get_mut(..len)?;
+    if start == 1 {
+        frame[0] = SYNC_WORD;
+    }
+    frame[start..].copy_from_slice(bytes);
+    Some(len)
+}
+
+/// Encode a command (e.g. START/SYNC or RDATA), returns the length of the frame
+/// or `None` if the buffer is too small
+pub fn encode_command(transport: Transport, command: Commands, buffer: &mut [u8]) -> Option<usize> {
+    encode(transport, &[command as u8], buffer)
+}
+
+/// Encode a register read (RREG), the response is a single byte.
+/// Returns the length of the frame or `None` if the buffer is too small.
+pub fn encode_read_register(transport: Transport, register: u8, buffer: &mut [u8]) -> Option<usize> {
+    encode(transport, &[rreg_command(register)], buffer)
+}
+
+/// Encode a register write (WREG), returns the length of the frame or `None` if the buffer is too small
+pub fn encode_write_register(transport: Transport, register: u8, value: u8, buffer: &mut [u8]) -> Option<usize> {
+    encode(transport, &[wreg_command(register), value], buffer)
+}
+
+/// Encode a conversion data read (RDATA), the response length is given by `data_frame_len()`.
+/// Returns the length of the frame or `None` if the buffer is too small.
+pub fn encode_read_data(transport: Transport, buffer: &mut [u8]) -> Option<usize> {
+    encode_command(transport, Commands::RData, buffer)
+}
+
+/// Decode the response to a register read
+pub fn decode_register(response: &[u8]) -> Option<u8> {
+    match response {
+        [value] => Some(*value),
+        _ => None,
+    }
+}
@@ -0,0 +1,67 @@
+//! Multi-chip sampling with aligned conversion start
+
+#[cfg(feature = "i2c")]
+use embedded_hal::i2c::I2c;
+
+use crate::interface::{ReadData, WriteData};
+#[cfg(feature = "i2c")]
+use crate::interface::I2cInterface;
+use crate::{Error, ADS122x04};
+
+/// Group of devices whose conversions are started together, so channels across chips sample at aligned instants
+pub struct DeviceGroup<BUS, const N: usize> {
+    devices: [ADS122x04<BUS>; N],
+}
+
+impl<BUS, E, const N: usize> DeviceGroup<BUS, N>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Create a group of devices
+    pub fn new(devices: [ADS122x04<BUS>; N]) -> Self {
+        DeviceGroup { devices }
+    }
+
+    /// Access the devices, e.g. to configure them or read their conversions
+    pub fn devices(&mut self) -> &mut [ADS122x04<BUS>; N] {
+        &mut self.devices
+    }
+
+    /// Destroy the group and return the devices
+    pub fn release(self) -> [ADS122x04<BUS>; N] {
+        self.devices
+    }
+
+    /// Start the conversions of all devices one after the other with minimal delay in between.
+    /// Returns the skew between the first and the last start, measured with the clock of the devices
+    /// (`set_clock()`) if the first and the last device have one.
+    pub fn start_all_sequential(&mut self) -> Result<Option<u64>, Error<E>> {
+        let mut first = None;
+        for (i, device) in self.devices.iter_mut().enumerate() {
+            device.start()?;
+            if i == 0 {
+                first = device.timestamp();
+            }
+        }
+        let last = self.devices.last().and_then(|device| device.timestamp());
+        Ok(first.zip(last).map(|(first, last)| last.saturating_sub(first)))
+    }
+}
+
+#[cfg(feature = "i2c")]
+impl<I2C, E, const N: usize> DeviceGroup<I2cInterface<I2C>, N>
+    where
+        I2C: I2c<Error=E>,
+{
+    /// Start the conversions of all devices with a single START/SYNC command sent to the I2C general-call
+    /// address. All devices must share the bus of the first device, every ADS122C04 on that bus is started.
+    pub fn start_all_synchronized(&mut self) -> Result<(), Error<E>> {
+        if let Some(first) = self.devices.first_mut() {
+            first.general_call_start()?;
+        }
+        for device in self.devices.iter_mut() {
+            device.powered_down = false;
+        }
+        Ok(())
+    }
+}
@@ -1,26 +1,147 @@
 //! I2C/UART interfaces
+//!
+//! The I2C interface is available with the `i2c` feature, the UART interface with the `uart` feature.
 
-use embedded_hal::{
-    blocking::i2c,
-    blocking::serial,
-    serial as serial_nb,
-};
-use nb::block;
+#[cfg(feature = "i2c")]
+use embedded_hal::i2c::{ErrorType as I2cErrorType, I2c};
+#[cfg(feature = "uart")]
+use embedded_io::{ErrorType, Read, ReadReady, Write};
 
-use crate::{Error, private};
+use crate::private;
+#[cfg(any(feature = "i2c", feature = "uart"))]
+use crate::Error;
+#[cfg(any(feature = "i2c", feature = "uart"))]
+use crate::frame::{rreg_command, wreg_command};
+#[cfg(feature = "uart")]
+use crate::frame::SYNC_WORD;
 use crate::registers::*;
 
+/// Possible I2C addresses of the ADS122C04, selected by strapping A1 and A0 to DGND, DVDD, SDA or SCL
+pub const I2C_ADDRESSES: [u8; 16] = [
+    0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4A, 0x4B, 0x4C, 0x4D, 0x4E, 0x4F,
+];
+
+/// I2C general-call address, commands sent to it are executed by all ADS122C04 devices on the bus
+pub const GENERAL_CALL_ADDRESS: u8 = 0x00;
+
 /// I2C interface
+///
+/// Works with any handler implementing the embedded-hal `I2c` trait, including the shared bus devices of
+/// embedded-hal-bus (`RefCellDevice`, `CriticalSectionDevice`, `MutexDevice`), so the bus is not owned exclusively
+/// by the driver.
+#[cfg(feature = "i2c")]
 #[derive(Debug)]
-pub struct I2cInterface<I2C> {
+pub struct I2cInterface<I2C: I2cErrorType> {
     pub(crate) i2c: I2C,
     pub(crate) address: u8,
+    /// bus multiplexer channel selected before every transaction
+    pub(crate) channel_select: Option<(ChannelSelect<I2C>, u8)>,
+}
+
+/// Selects a channel of an I2C bus multiplexer, called with the I2C handler and the channel number
+#[cfg(feature = "i2c")]
+pub type ChannelSelect<I2C> = fn(&mut I2C, u8) -> Result<(), <I2C as I2cErrorType>::Error>;
+
+/// Channel select for the TCA9548A/TCA9546A/TCA9543A I2C multiplexers (one control register bit per channel)
+/// at `ADDRESS`, to be passed as `tca954x_select::<_, 0x70>`
+#[cfg(feature = "i2c")]
+pub fn tca954x_select<I2C: I2c, const ADDRESS: u8>(i2c: &mut I2C, channel: u8) -> Result<(), I2C::Error> {
+    i2c.write(ADDRESS, &[1 << (channel & 0x07)])
+}
+
+#[cfg(feature = "i2c")]
+impl<I2C, E> I2cInterface<I2C>
+    where
+        I2C: I2c<Error=E>,
+{
+    /// select the bus multiplexer channel of the device, if any
+    pub(crate) fn select_channel(&mut self) -> Result<(), Error<E>> {
+        if let Some((select, channel)) = self.channel_select {
+            select(&mut self.i2c, channel).map_err(Error::CommError)?;
+        }
+        Ok(())
+    }
 }
 
+/// maximum number of stale bytes discarded when resynchronizing the UART interface
+#[cfg(feature = "uart")]
+const MAX_STALE_BYTES: usize = 256;
+
 /// UART interface
+#[cfg(feature = "uart")]
 #[derive(Debug)]
 pub struct SerialInterface<UART> {
     pub(crate) serial: UART,
+    /// number of polls before a read times out, `None` blocks forever
+    pub(crate) read_timeout: Option<u32>,
+}
+
+#[cfg(feature = "uart")]
+impl<UART, E> SerialInterface<UART>
+    where
+        UART: Read + Write + ReadReady + ErrorType<Error=E>,
+{
+    /// read a single byte, returns `Error::Timeout` if the read timeout is exceeded and `Error::UnexpectedEof`
+    /// if the UART reports end-of-file
+    fn read_byte(&mut self) -> Result<u8, Error<E>> {
+        let mut polls = 0;
+        let mut byte = [0];
+        loop {
+            if self.serial.read_ready().map_err(Error::CommError)? {
+                return match self.serial.read(&mut byte).map_err(Error::CommError)? {
+                    0 => Err(Error::UnexpectedEof),
+                    _ => Ok(byte[0]),
+                };
+            }
+            polls += 1;
+            if self.read_timeout.is_some_and(|timeout| polls > timeout) {
+                return Err(Error::Timeout);
+            }
+        }
+    }
+
+    /// read bytes until the buffer is full
+    pub(crate) fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        for byte in buffer.iter_mut() {
+            *byte = self.read_byte()?;
+        }
+        Ok(())
+    }
+
+    /// send a command `count` times, flushing only once
+    pub(crate) fn send_commands(&mut self, command: Commands, count: usize) -> Result<(), Error<E>> {
+        for _ in 0..count {
+            self.serial.write_all(&[SYNC_WORD, command as u8]).map_err(Error::CommError)?;
+        }
+        self.serial.flush().map_err(Error::CommError)
+    }
+}
+
+/// Bus transaction reported to the trace hook
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Transaction {
+    /// A register has been written
+    WriteRegister {
+        /// register address
+        register: u8,
+        /// written value
+        value: u8,
+    },
+    /// A register has been read
+    ReadRegister {
+        /// register address
+        register: u8,
+        /// value read back
+        value: u8,
+    },
+    /// The conversion data has been read (RDATA)
+    ReadData {
+        /// raw 24-bit conversion data
+        value: u32,
+    },
+    /// A command has been sent
+    Command(Commands),
 }
 
 /// Write data
@@ -31,39 +152,57 @@ pub trait WriteData: private::Sealed {
     fn write_register(&mut self, register: u8, data: u8) -> Result<(), Self::Error>;
     /// Write data. The first element corresponds to the starting address.
     fn write_data(&mut self, payload: u8) -> Result<(), Self::Error>;
+    /// Resynchronize the interface after a communication glitch
+    fn resync(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
 
+#[cfg(feature = "i2c")]
 impl<I2C, E> WriteData for I2cInterface<I2C>
     where
-        I2C: i2c::Write<Error=E>,
+        I2C: I2c<Error=E>,
 {
     type Error = Error<E>;
     fn write_register(&mut self, register: u8, data: u8) -> Result<(), Self::Error> {
-        let register = Commands::WReg as u8 | (register << 2); // write command
+        self.select_channel()?;
+        let register = wreg_command(register);
         self.i2c
             .write(self.address, &[register, data])
             .map_err(Error::CommError)
     }
 
     fn write_data(&mut self, payload: u8) -> Result<(), Self::Error> {
+        self.select_channel()?;
         self.i2c.write(self.address, &[payload]).map_err(Error::CommError)
     }
 }
 
+#[cfg(feature = "uart")]
 impl<UART, E> WriteData for SerialInterface<UART>
     where
-        UART: serial::Write<u8, Error=E> + serial_nb::Read<u8, Error=E>,
+        UART: Read + Write + ReadReady + ErrorType<Error=E>,
 {
     type Error = Error<E>;
     fn write_register(&mut self, register: u8, data: u8) -> Result<(), Self::Error> {
-        let register = Commands::WReg as u8 | (register << 2); // write command
-        self.serial.bwrite_all(&[0x55, register, data]).map_err(Error::CommError)?;
-        self.serial.bflush().map_err(Error::CommError)
+        let register = wreg_command(register);
+        self.serial.write_all(&[SYNC_WORD, register, data]).map_err(Error::CommError)?;
+        self.serial.flush().map_err(Error::CommError)
     }
 
     fn write_data(&mut self, payload: u8) -> Result<(), Self::Error> {
-        self.serial.bwrite_all(&[0x55, payload]).map_err(Error::CommError)?;
-        self.serial.bflush().map_err(Error::CommError)
+        self.serial.write_all(&[SYNC_WORD, payload]).map_err(Error::CommError)?;
+        self.serial.flush().map_err(Error::CommError)
+    }
+
+    /// Discard stale received bytes and re-send the synchronization word
+    fn resync(&mut self) -> Result<(), Self::Error> {
+        let mut stale = [0; MAX_STALE_BYTES];
+        if self.serial.read_ready().map_err(Error::CommError)? {
+            self.serial.read(&mut stale).map_err(Error::CommError)?;
+        }
+        self.serial.write_all(&[SYNC_WORD]).map_err(Error::CommError)?;
+        self.serial.flush().map_err(Error::CommError)
     }
 }
 
@@ -75,15 +214,19 @@ pub trait ReadData: private::Sealed {
     fn read_register(&mut self, register: u8) -> Result<u8, Self::Error>;
     /// Read some data. The first element corresponds to the starting address.
     fn read_data(&mut self) -> Result<u32, Self::Error>;
+    /// Read the complete conversion data frame (data counter, data and CRC) in a single transaction
+    fn read_frame(&mut self, frame: &mut [u8]) -> Result<(), Self::Error>;
 }
 
+#[cfg(feature = "i2c")]
 impl<I2C, E> ReadData for I2cInterface<I2C>
     where
-        I2C: i2c::WriteRead<Error=E>,
+        I2C: I2c<Error=E>,
 {
     type Error = Error<E>;
     fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
-        let register = Commands::RReg as u8 | (register << 2); // read command
+        self.select_channel()?;
+        let register = rreg_command(register);
         let mut buffer = [0];
         self.i2c
             .write_read(self.address, &[register], &mut buffer)
@@ -92,6 +235,7 @@ impl<I2C, E> ReadData for I2cInterface<I2C>
     }
 
     fn read_data(&mut self) -> Result<u32, Self::Error> {
+        self.select_channel()?;
         let mut buffer = [0, 0, 0];
         self.i2c
             .write_read(self.address, &[Commands::RData as u8], &mut buffer)
@@ -103,26 +247,40 @@ impl<I2C, E> ReadData for I2cInterface<I2C>
             })
             .map_err(Error::CommError)
     }
+
+    fn read_frame(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        self.select_channel()?;
+        self.i2c
+            .write_read(self.address, &[Commands::RData as u8], frame)
+            .map_err(Error::CommError)
+    }
 }
 
+#[cfg(feature = "uart")]
 impl<UART, E> ReadData for SerialInterface<UART>
     where
-        UART: serial::Write<u8, Error=E> + serial_nb::Read<u8, Error=E>,
+        UART: Read + Write + ReadReady + ErrorType<Error=E>,
 {
     type Error = Error<E>;
     fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
-        let register = Commands::RReg as u8 | (register << 2); // read command
-        self.serial.bwrite_all(&[0x55, register]).map_err(Error::CommError)?;
-        self.serial.bflush().map_err(Error::CommError)?;
-        block!(self.serial.read()).map_err(Error::CommError)
+        let register = rreg_command(register);
+        self.serial.write_all(&[SYNC_WORD, register]).map_err(Error::CommError)?;
+        self.serial.flush().map_err(Error::CommError)?;
+        self.read_byte()
     }
 
     fn read_data(&mut self) -> Result<u32, Self::Error> {
-        self.serial.bwrite_all(&[0x55, Commands::RData as u8]).map_err(Error::CommError)?;
-        self.serial.bflush().map_err(Error::CommError)?;
-        let msb = block!(self.serial.read()).map_err(Error::CommError)?;
-        let csb = block!(self.serial.read()).map_err(Error::CommError)?;
-        let lsb = block!(self.serial.read()).map_err(Error::CommError)?;
+        self.serial.write_all(&[SYNC_WORD, Commands::RData as u8]).map_err(Error::CommError)?;
+        self.serial.flush().map_err(Error::CommError)?;
+        let msb = self.read_byte()?;
+        let csb = self.read_byte()?;
+        let lsb = self.read_byte()?;
         Ok((msb as u32) << 16 | (csb as u32) << 8 | (lsb as u32))
     }
+
+    fn read_frame(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        self.serial.write_all(&[SYNC_WORD, Commands::RData as u8]).map_err(Error::CommError)?;
+        self.serial.flush().map_err(Error::CommError)?;
+        self.read_bytes(frame)
+    }
 }
\ No newline at end of file
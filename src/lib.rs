@@ -4,34 +4,103 @@
 #![deny(missing_docs)]
 #![deny(warnings)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use core::fmt::Debug;
 use core::result::Result;
 use core::result::Result::Err;
 
-use embedded_hal::{
-    blocking::i2c,
-    blocking::serial,
-    serial as serial_nb,
-};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::InputPin;
+#[cfg(feature = "i2c")]
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "uart")]
+use embedded_io::{ErrorType, Read, ReadReady, Write};
 
-use crate::{interface::{I2cInterface, ReadData, SerialInterface, WriteData}};
+use crate::interface::{ReadData, Transaction, WriteData};
+#[cfg(feature = "i2c")]
+use crate::interface::{ChannelSelect, I2cInterface};
+#[cfg(feature = "uart")]
+use crate::interface::SerialInterface;
+use crate::filter::Estimator;
+use crate::codec::*;
 use crate::registers::*;
 
 pub mod registers;
+pub mod codec;
+pub mod frame;
 pub mod interface;
+#[cfg(feature = "embedded-hal-02")]
+pub mod adc;
+#[cfg(feature = "embedded-hal-02")]
+pub mod compat;
+#[cfg(feature = "linux")]
+pub mod linux;
+pub mod filter;
+pub mod stream;
+pub mod low_power;
+pub mod group;
+pub mod split;
+pub mod sink;
+pub mod format;
+#[cfg(feature = "critical-section")]
+pub mod cs;
+#[cfg(any(
+    feature = "thermocouple-j",
+    feature = "thermocouple-t",
+    feature = "thermocouple-e"
+))]
+pub mod thermocouple;
+#[cfg(feature = "float")]
+pub mod thermistor;
+#[cfg(feature = "float")]
+pub mod rtd;
+#[cfg(feature = "float")]
+pub mod bridge;
+#[cfg(feature = "float")]
+pub mod current_loop;
+#[cfg(feature = "float")]
+pub mod divider;
+#[cfg(feature = "float")]
+pub mod ph;
+#[cfg(feature = "float")]
+pub mod scale;
+#[cfg(feature = "float")]
+pub mod linearize;
+#[cfg(feature = "float")]
+pub mod polynomial;
+#[cfg(feature = "float")]
+pub mod logger;
+#[cfg(feature = "float")]
+pub mod scan;
+#[cfg(feature = "float")]
+pub mod statistics;
+#[cfg(feature = "float")]
+pub mod sensor;
+#[cfg(feature = "uom")]
+pub mod units;
+#[cfg(feature = "heapless")]
+pub mod recorder;
+#[cfg(feature = "mock")]
+pub mod mock;
 
 
 mod private {
+    #[cfg(any(feature = "i2c", feature = "uart"))]
     use super::interface;
 
     pub trait Sealed {}
 
+    #[cfg(feature = "uart")]
     impl<UART> Sealed for interface::SerialInterface<UART> {}
 
-    impl<I2C, > Sealed for interface::I2cInterface<I2C> {}
+    #[cfg(feature = "i2c")]
+    impl<I2C: embedded_hal::i2c::ErrorType> Sealed for interface::I2cInterface<I2C> {}
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Error enum for ADS122x04
 pub enum Error<E>
 {
@@ -43,84 +112,658 @@ pub enum Error<E>
     InvalidValue,
     /// A timeout has occurred
     Timeout,
+    /// The input is saturated (reading pinned at full scale)
+    Saturated,
+    /// The CRC of the received data does not match
+    CrcMismatch {
+        /// CRC calculated from the received data
+        expected: u16,
+        /// CRC received from the device
+        got: u16,
+    },
+    /// No new conversion data is available
+    DataNotReady,
+    /// The requested configuration conflicts with the current configuration
+    ConfigConflict(&'static str),
+    /// The device has fallen back to its reset configuration (e.g. brown-out)
+    DeviceResetDetected,
+    /// The data ready (DRDY) pin could not be read
+    PinError,
+    /// The device is being accessed through another handle
+    Busy,
+    /// The UART reached end-of-file before the complete response was received
+    UnexpectedEof,
     /// A communication error has occured
     CommError(E),
 }
 
+impl<E: Debug> core::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::ShortedInput => write!(f, "the input is shorted"),
+            Error::OpenInput => write!(f, "the input is open"),
+            Error::InvalidValue => write!(f, "an invalid value has been entered"),
+            Error::Timeout => write!(f, "a timeout has occurred"),
+            Error::Saturated => write!(f, "the input is saturated"),
+            Error::CrcMismatch { expected, got } => {
+                write!(f, "CRC mismatch: expected {:#06x}, got {:#06x}", expected, got)
+            }
+            Error::DataNotReady => write!(f, "no new conversion data is available"),
+            Error::ConfigConflict(reason) => write!(f, "configuration conflict: {}", reason),
+            Error::DeviceResetDetected => write!(f, "the device has been reset unexpectedly"),
+            Error::PinError => write!(f, "the data ready pin could not be read"),
+            Error::Busy => write!(f, "the device is being accessed through another handle"),
+            Error::UnexpectedEof => write!(f, "the UART reached end-of-file before the response was complete"),
+            Error::CommError(e) => write!(f, "communication error: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Debug> std::error::Error for Error<E> {}
+
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Result of a conversion
+pub struct ConversionResult {
+    /// raw ADC value with the offset subtracted
+    pub raw: i32,
+    /// data counter, if enabled with `set_data_counter()`
+    pub counter: Option<u8>,
+    /// timestamp of the read from the clock set with `set_clock()`, if any
+    pub timestamp: Option<u64>,
+    saturated: bool,
+}
+
+impl core::fmt::Display for ConversionResult {
+    /// Raw value followed by the data counter, the timestamp and a saturation marker, if any
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.raw)?;
+        if let Some(counter) = self.counter {
+            write!(f, " #{}", counter)?;
+        }
+        if let Some(timestamp) = self.timestamp {
+            write!(f, " @{}", timestamp)?;
+        }
+        if self.saturated {
+            write!(f, " (saturated)")?;
+        }
+        Ok(())
+    }
+}
+
+impl ConversionResult {
+    /// The reading is pinned at positive or negative full scale (0x7FFFFF / 0x800000),
+    /// so it does not represent the real input value
+    pub fn is_saturated(&self) -> bool {
+        self.saturated
+    }
+
+    /// Encode the result with postcard into `buffer`, returns the used part of the buffer
+    #[cfg(feature = "postcard")]
+    pub fn to_postcard<'a>(&self, buffer: &'a mut [u8]) -> postcard::Result<&'a mut [u8]> {
+        postcard::to_slice(self, buffer)
+    }
+
+    /// Decode a result encoded with `to_postcard()`
+    #[cfg(feature = "postcard")]
+    pub fn from_postcard(bytes: &[u8]) -> postcard::Result<Self> {
+        postcard::from_bytes(bytes)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Average of multiple conversions
+pub struct AveragedReading {
+    /// mean of the raw ADC values
+    pub mean: i32,
+    /// smallest raw ADC value
+    pub min: i32,
+    /// largest raw ADC value
+    pub max: i32,
+}
+
+impl AveragedReading {
+    /// Spread (peak-to-peak) of the raw ADC values
+    pub fn spread(&self) -> i32 {
+        self.max - self.min
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Retry policy for transient communication errors (e.g. NACK, UART framing errors)
+pub struct RetryPolicy {
+    /// number of attempts per bus transaction, including the first one
+    pub attempts: u8,
+    /// number of spin loop iterations to wait between attempts
+    pub backoff: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 1,
+            backoff: 0,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Sensor status as detected with the burnout current sources
+pub enum SensorStatus {
+    /// The sensor is connected
+    Ok,
+    /// The sensor is open (wire break)
+    Open,
+    /// The sensor is shorted
+    Shorted,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Conversion result of `read_with_burnout_check()` annotated with the sensor status
+pub struct BurnoutCheckedResult {
+    /// the conversion result, taken with the burnout current sources in their configured state
+    pub result: ConversionResult,
+    /// sensor status of the latest burnout check
+    pub sensor: SensorStatus,
+    /// the burnout check was done for this reading, otherwise `sensor` is from an earlier reading
+    pub checked: bool,
+}
+
+impl BurnoutCheckedResult {
+    /// The latest burnout check found an open sensor
+    pub fn wire_break(&self) -> bool {
+        self.sensor == SensorStatus::Open
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Conversion status returned by `status()`
+pub struct DeviceStatus {
+    /// new conversion data is available (DRDY)
+    pub data_ready: bool,
+    /// conversion mode (CM)
+    pub conversion_mode: ConversionMode,
+    /// operating mode (MODE)
+    pub operating_mode: OperatingMode,
+    /// data counter of the last conversion read, `None` if the data counter is disabled on the device
+    pub counter: Option<u8>,
+}
+
+/// number of DRDY polls before a timeout occurs
+const DATA_READY_TIMEOUT: u32 = 1000;
+/// readings above this absolute value are considered saturated (about 95 % of full scale)
+const OPEN_SENSOR_THRESHOLD: i32 = 0x7A0000;
+/// readings below this absolute value are considered shorted when the burnout sources are enabled
+const SHORTED_SENSOR_THRESHOLD: i32 = 0x100;
+/// auto-ranging steps the gain down above this absolute reading (about 90 % of full scale)
+const AUTO_GAIN_UPPER_THRESHOLD: i32 = 0x733333;
+/// auto-ranging steps the gain up below this absolute reading (about 45 % of full scale)
+const AUTO_GAIN_LOWER_THRESHOLD: i32 = 0x39999A;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Written and read back value of a configuration register
+pub struct RegisterCheck {
+    /// value written from the cached configuration
+    pub expected: u8,
+    /// value read back from the device, with DRDY and the reserved bits cleared
+    pub readback: u8,
+}
+
+impl RegisterCheck {
+    /// The register holds the written value
+    pub fn is_ok(&self) -> bool {
+        self.expected == self.readback
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Result of the bring-up with `init()`
+pub struct InitReport {
+    /// verification of the configuration registers 0 to 3
+    pub registers: [RegisterCheck; 4],
+}
+
+impl InitReport {
+    /// All configuration registers hold the written values
+    pub fn is_ok(&self) -> bool {
+        self.registers.iter().all(RegisterCheck::is_ok)
+    }
+}
+
+#[cfg(feature = "float")]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Result of `self_test()`
+pub struct DiagnosticsReport {
+    /// raw reading with the inputs shorted (offset calibration not applied)
+    pub offset: i32,
+    /// reference voltage (V) measured with the VREF monitor
+    pub reference_voltage: f32,
+    /// analog supply voltage AVDD - AVSS (V) measured with the AVDD monitor
+    pub supply_voltage: f32,
+    /// sensor status detected with the burnout current sources on the configured input
+    pub sensor: SensorStatus,
+    /// internal temperature (°C)
+    pub temperature: f32,
+    /// the offset is below the limit
+    pub offset_ok: bool,
+    /// the reference voltage matches the configured reference within 2 %
+    pub reference_ok: bool,
+    /// the supply voltage is within the operating range of 2.3 V to 5.5 V
+    pub supply_ok: bool,
+    /// the internal temperature is within the operating range of -40 °C to 125 °C
+    pub temperature_ok: bool,
+}
+
+#[cfg(feature = "float")]
+impl DiagnosticsReport {
+    /// All checks passed and the sensor is connected
+    pub fn is_ok(&self) -> bool {
+        self.offset_ok
+            && self.reference_ok
+            && self.supply_ok
+            && self.temperature_ok
+            && self.sensor == SensorStatus::Ok
+    }
+}
+
+#[cfg(feature = "float")]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Alarm thresholds (V) of a monitored rail, see `set_reference_limits()` and `set_supply_limits()`
+pub struct RailLimits {
+    /// lowest accepted voltage
+    pub min: f32,
+    /// highest accepted voltage
+    pub max: f32,
+}
+
+#[cfg(feature = "float")]
+impl RailLimits {
+    /// Classify a measured rail voltage
+    pub fn check(&self, voltage: f32) -> RailAlarm {
+        if voltage < self.min {
+            RailAlarm::Low
+        } else if voltage > self.max {
+            RailAlarm::High
+        } else {
+            RailAlarm::Ok
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Alarm state of a monitored rail
+pub enum RailAlarm {
+    /// The voltage is within the limits, or no limits are set
+    Ok,
+    /// The voltage is below the minimum
+    Low,
+    /// The voltage is above the maximum
+    High,
+}
+
+#[cfg(feature = "float")]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Result of `check_rails()`
+pub struct RailsReport {
+    /// reference voltage (V) measured with the VREF monitor
+    pub reference_voltage: f32,
+    /// analog supply voltage AVDD - AVSS (V) measured with the AVDD monitor
+    pub supply_voltage: f32,
+    /// alarm state of the reference voltage
+    pub reference: RailAlarm,
+    /// alarm state of the supply voltage
+    pub supply: RailAlarm,
+}
+
+#[cfg(feature = "float")]
+impl RailsReport {
+    /// Both rails are within their limits
+    pub fn is_ok(&self) -> bool {
+        self.reference == RailAlarm::Ok && self.supply == RailAlarm::Ok
+    }
+}
+
+/// maximum raw offset with shorted inputs accepted by `self_test()`
+#[cfg(feature = "float")]
+const SELF_TEST_MAX_OFFSET: i32 = 0x400;
+/// relative deviation of the measured reference voltage accepted by `self_test()` and `check_reference()`
+#[cfg(feature = "float")]
+const REFERENCE_TOLERANCE: f32 = 0.02;
+/// settling time of the input filter after enabling the burnout current sources in us
+#[cfg(feature = "float")]
+const BURNOUT_SETTLING_US: u32 = 1000;
+/// the VREF and AVDD monitors measure a quarter of the voltage
+#[cfg(feature = "float")]
+const MONITOR_DIVIDER: f32 = 4.0;
+
+/// start-up time of the excitation current sources in us
+const IDAC_STARTUP_US: u32 = 200;
+/// delay from the RESET command until the device accepts commands again in us
+const RESET_DELAY_US: u32 = 50;
+/// maximum number of reads in `read_conversion_consistent()` and `start_and_wait()`
+const CONSISTENT_READ_ATTEMPTS: u32 = 3;
+/// writable bits of the configuration registers 0 to 3, DRDY and the reserved bits are ignored on readback
+const REGISTER_MASKS: [u8; 4] = [0xFF, 0xFF, 0x7F, 0xFC];
+
+/// classify a reading taken with the burnout current sources enabled
+fn sensor_status(raw: i32) -> SensorStatus {
+    if raw.abs() >= OPEN_SENSOR_THRESHOLD {
+        SensorStatus::Open
+    } else if raw.abs() <= SHORTED_SENSOR_THRESHOLD {
+        SensorStatus::Shorted
+    } else {
+        SensorStatus::Ok
+    }
+}
+
 /// Device handler for ADS122x04
 pub struct ADS122x04<BUS>
 {
     bus: BUS,
     /// offset of the ADC
     pub offset: i32,
-    v_ref: VRef,
-    gain: Gain,
-    mux: Mux,
-    current_source: CurrentSource,
-    current_route_1: CurrentRoute,
-    current_route_2: CurrentRoute,
-    data_rate: DataRate,
-    pga_bypass: bool,
-    turbo_mode: bool,
-    conversion_mode: ConversionMode,
-    temperature_sensor_mode: bool,
-    data_counter_enable: bool,
-    crc: Crc,
-    burn_out_current_sources: bool,
+    config: DeviceConfig,
+    /// input voltage of one LSB, precomputed from the configuration
+    #[cfg(feature = "float")]
+    lsb_voltage: f32,
+    /// voltage of an external or supply reference in uV for the integer conversion path
+    reference_microvolts: u32,
+    /// last values written to the configuration registers, `None` if unknown
+    written: [Option<u8>; 4],
+    error_on_saturation: bool,
+    retry_policy: RetryPolicy,
+    trace_hook: Option<fn(&Transaction)>,
+    clock: Option<fn() -> u64>,
+    oneshot_pending: bool,
+    auto_power_down: bool,
+    powered_down: bool,
+    auto_restart: bool,
+    unsettled: u8,
+    /// conversions discarded after a configuration change that needs settling
+    settling_discards: u8,
+    /// data counter of the last conversion read with the data counter enabled
+    last_counter: Option<u8>,
+    /// reads between burnout checks in `read_with_burnout_check()`, 0 disables the checks
+    burnout_check_interval: u32,
+    /// reads until the next burnout check
+    burnout_countdown: u32,
+    /// sensor status of the last burnout check
+    sensor_status: SensorStatus,
+    #[cfg(feature = "float")]
+    reference_fallback: bool,
+    #[cfg(feature = "float")]
+    reference_fallback_active: bool,
+    #[cfg(feature = "float")]
+    reference_limits: Option<RailLimits>,
+    #[cfg(feature = "float")]
+    supply_limits: Option<RailLimits>,
 }
 
+impl<BUS> ADS122x04<BUS> {
+    /// create the driver with the reset configuration on the given interface
+    fn with_bus(bus: BUS) -> Self {
+        ADS122x04 {
+            bus,
+            offset: 0,
+            config: DeviceConfig::default(),
+            #[cfg(feature = "float")]
+            lsb_voltage: DeviceConfig::default().lsb_voltage(),
+            reference_microvolts: INTERNAL_REFERENCE_MICROVOLTS,
+            written: [None; 4],
+            error_on_saturation: false,
+            retry_policy: RetryPolicy::default(),
+            trace_hook: None,
+            clock: None,
+            oneshot_pending: false,
+            auto_power_down: false,
+            powered_down: false,
+            auto_restart: false,
+            unsettled: 0,
+            settling_discards: 1,
+            last_counter: None,
+            burnout_check_interval: 1,
+            burnout_countdown: 0,
+            sensor_status: SensorStatus::Ok,
+            #[cfg(feature = "float")]
+            reference_fallback: false,
+            #[cfg(feature = "float")]
+            reference_fallback_active: false,
+            #[cfg(feature = "float")]
+            reference_limits: None,
+            #[cfg(feature = "float")]
+            supply_limits: None,
+        }
+    }
+}
+
+#[cfg(feature = "i2c")]
 impl<I2C, E> ADS122x04<I2cInterface<I2C>>
     where
-        I2C: i2c::Write<Error=E> + i2c::WriteRead<Error=E>,
+        I2C: I2c<Error=E>,
 {
-    /// Create a new ADS122C04 device by supplying an I2C address and I2C handler
+    /// Create a new ADS122C04 device by supplying an I2C address and I2C handler.
+    ///
+    /// The driver only requires the embedded-hal `I2c` trait, so the bus can be shared with other devices by
+    /// passing an embedded-hal-bus device instead of the I2C peripheral itself:
+    ///
+    /// ```ignore
+    /// let bus = RefCell::new(i2c);
+    /// let mut adc = ADS122x04::new_i2c(0x40, RefCellDevice::new(&bus));
+    /// let mut other_sensor = OtherSensor::new(RefCellDevice::new(&bus));
+    /// ```
+    ///
+    /// HALs still implementing embedded-hal 0.2 can be wrapped in `compat::I2cCompat` with the `embedded-hal-02`
+    /// feature.
     pub fn new_i2c(address: u8, i2c: I2C) -> Self
     {
-        ADS122x04 {
-            bus: I2cInterface { i2c, address },
-            offset: 0,
-            v_ref: VRef::Internal,
-            gain: Gain::Gain1,
-            mux: Mux::Ain0Ain1,
-            current_source: CurrentSource::Off,
-            current_route_1: CurrentRoute::Off,
-            current_route_2: CurrentRoute::Off,
-            data_rate: DataRate::Sps20Normal,
-            pga_bypass: false,
-            turbo_mode: false,
-            conversion_mode: ConversionMode::SingleShot,
-            temperature_sensor_mode: false,
-            data_counter_enable: false,
-            crc: Crc::Disabled,
-            burn_out_current_sources: false,
+        Self::with_bus(I2cInterface { i2c, address, channel_select: None })
+    }
+
+    /// Create a new ADS122C04 device and bring it up with `config`: the device is reset, the configuration is
+    /// written and verified by readback (see `init()`) and the conversions are started in continuous mode.
+    /// Returns `Error::ConfigConflict` if `config` has conflicting settings or a register does not read back the
+    /// written value.
+    pub fn new_i2c_with_config<D: DelayNs>(
+        address: u8,
+        i2c: I2C,
+        config: DeviceConfig,
+        delay: &mut D,
+    ) -> Result<Self, Error<E>> {
+        let mut adc = Self::new_i2c(address, i2c);
+        adc.bring_up(config, delay)?;
+        Ok(adc)
+    }
+
+    /// I2C address the driver talks to
+    pub fn address(&self) -> u8 {
+        self.bus.address
+    }
+
+    /// Retarget the driver to another I2C address, e.g. after re-strapping A0/A1 or switching a bus multiplexer.
+    /// The cached configuration and calibration are kept, use `check_device_health(true)` if the device at the
+    /// new address may hold a different configuration.
+    pub fn set_address(&mut self, address: u8) {
+        self.bus.address = address;
+        // the registers of the device at the new address are unknown, do not skip any write
+        self.written = [None; 4];
+    }
+
+    /// Select `channel` of an I2C bus multiplexer with `select` before every transaction, so many devices behind a
+    /// multiplexer (e.g. `interface::tca954x_select::<_, 0x70>`) can share the bus. `None` disables the selection.
+    pub fn set_channel_select(&mut self, select: Option<ChannelSelect<I2C>>, channel: u8) {
+        self.bus.channel_select = select.map(|select| (select, channel));
+        // the registers of the device behind the new channel are unknown, do not skip any write
+        self.written = [None; 4];
+    }
+
+    /// Destroy the driver and return the I2C handler
+    pub fn release(self) -> I2C {
+        self.bus.i2c
+    }
+
+    /// Probe the given addresses (e.g. `interface::I2C_ADDRESSES`) and return a device bound to the first address
+    /// that responds sensibly: the configuration registers either read back the reset defaults or a written
+    /// test pattern. Returns the I2C handler if no device was found.
+    /// The cached configuration is not synchronized with the device, call `reset(delay)` after probing.
+    pub fn probe(i2c: I2C, addresses: &[u8]) -> Result<Self, I2C> {
+        let mut device = Self::new_i2c(0, i2c);
+        for &address in addresses {
+            device.bus.address = address;
+            if let Ok(true) = device.responds() {
+                return Ok(device);
+            }
         }
+        Err(device.release())
+    }
+
+    /// Reset all ADS122C04 devices on the bus simultaneously using the I2C general-call address.
+    /// The cached configuration of this handle is reset to the power-on defaults like with `reset()`, other
+    /// handles to devices on the bus keep their cached configuration.
+    pub fn general_call_reset(&mut self) -> Result<(), Error<E>> {
+        self.bus.select_channel()?;
+        self.bus
+            .i2c
+            .write(interface::GENERAL_CALL_ADDRESS, &[Commands::Reset as u8])
+            .map_err(Error::CommError)?;
+        self.reset_state();
+        Ok(())
+    }
+
+    /// Start a measurement on all ADS122C04 devices on the bus simultaneously using the I2C general-call address
+    pub fn general_call_start(&mut self) -> Result<(), Error<E>> {
+        self.bus.select_channel()?;
+        self.bus
+            .i2c
+            .write(interface::GENERAL_CALL_ADDRESS, &[Commands::StartSync as u8])
+            .map_err(Error::CommError)
+    }
+
+    /// check if the device at the current address behaves like an ADS122C04
+    fn responds(&mut self) -> Result<bool, Error<E>> {
+        let mut registers = [0; 4];
+        for (reg, val) in registers.iter_mut().enumerate() {
+            *val = self.read_reg(reg as u8)?;
+        }
+        // reset defaults, DRDY and the reserved bits are ignored
+        if registers[0] == 0 && registers[1] == 0 && registers[2] & 0x7F == 0 && registers[3] & 0xFC == 0 {
+            return Ok(true);
+        }
+        const TEST_PATTERN: u8 = 0xA5;
+        self.write_register(0x00, TEST_PATTERN)?;
+        let readback = self.read_reg(0x00)?;
+        self.write_register(0x00, registers[0])?;
+        Ok(readback == TEST_PATTERN)
     }
 }
 
+#[cfg(feature = "uart")]
 impl<UART, E> ADS122x04<SerialInterface<UART>>
     where
-        UART: serial::Write<u8, Error=E> + serial_nb::Read<u8, Error=E>,
+        UART: Read + Write + ReadReady + ErrorType<Error=E>,
 {
     /// Create a new ADS122C04 device by supplying a serial handler (UART)
     pub fn new_serial(serial: UART) -> Self {
-        ADS122x04 {
-            bus: SerialInterface { serial },
-            offset: 0,
-            v_ref: VRef::Internal,
-            gain: Gain::Gain1,
-            mux: Mux::Ain0Ain1,
-            current_source: CurrentSource::Off,
-            current_route_1: CurrentRoute::Off,
-            current_route_2: CurrentRoute::Off,
-            data_rate: DataRate::Sps20Normal,
-            pga_bypass: false,
-            turbo_mode: false,
-            conversion_mode: ConversionMode::SingleShot,
-            temperature_sensor_mode: false,
-            data_counter_enable: false,
-            crc: Crc::Disabled,
-            burn_out_current_sources: false,
+        Self::with_bus(SerialInterface { serial, read_timeout: None })
+    }
+
+    /// Create a new ADS122U04 device and bring it up with `config`: the device is reset, the configuration is
+    /// written and verified by readback (see `init()`) and the conversions are started in continuous mode.
+    /// Returns `Error::ConfigConflict` if `config` has conflicting settings or a register does not read back the
+    /// written value.
+    pub fn new_serial_with_config<D: DelayNs>(
+        serial: UART,
+        config: DeviceConfig,
+        delay: &mut D,
+    ) -> Result<Self, Error<E>> {
+        let mut adc = Self::new_serial(serial);
+        adc.bring_up(config, delay)?;
+        Ok(adc)
+    }
+
+    /// Destroy the driver and return the serial handler (UART)
+    pub fn release(self) -> UART {
+        self.bus.serial
+    }
+
+    /// Read `results.len()` conversions with minimal per-sample overhead: all RDATA commands are sent at once
+    /// and the data frames are read back afterwards, checking the CRC if enabled.
+    /// Each command returns the latest conversion at the time it is processed, so the data counter
+    /// (`set_data_counter()`) should be enabled to detect repeated conversions.
+    pub fn read_burst(&mut self, results: &mut [ConversionResult]) -> Result<(), Error<E>> {
+        let (counter, crc) = (self.config.data_counter_enable, self.config.crc);
+        let mut buffer = [0; MAX_DATA_FRAME_LEN];
+        let frame = &mut buffer[..data_frame_len(counter, crc)];
+        self.bus.send_commands(Commands::RData, results.len())?;
+        for result in results.iter_mut() {
+            self.bus.read_bytes(frame)?;
+            let data_frame = decode_data_frame(frame, counter, crc)?;
+            self.trace(Transaction::ReadData { value: data_frame.data });
+            self.last_counter = data_frame.counter;
+            *result = self.conversion_result(data_frame)?;
         }
+        Ok(())
+    }
+
+    /// Set the direction of a general purpose IO pin (configuration register 4)
+    pub fn set_gpio_direction(&mut self, pin: Gpio, direction: GpioDirection) -> Result<(), Error<E>> {
+        let bit = 1 << (pin as u8 + 4);
+        let val = self.read_register(0x04)?;
+        let val = match direction {
+            GpioDirection::Output => val & !bit,
+            GpioDirection::Input => val | bit,
+        };
+        self.write_register(0x04, val)
+    }
+
+    /// Set the level of a general purpose IO pin configured as output
+    pub fn set_gpio_output(&mut self, pin: Gpio, high: bool) -> Result<(), Error<E>> {
+        let bit = 1 << (pin as u8);
+        let val = self.read_register(0x04)?;
+        let val = if high { val | bit } else { val & !bit };
+        self.write_register(0x04, val)
+    }
+
+    /// Drive the data ready (DRDY) signal on GPIO2 instead of using it as general purpose IO,
+    /// so it can be used with `is_data_ready_pin()` and `measure_with_drdy_pin()`
+    pub fn set_gpio2_data_ready(&mut self, state: bool) -> Result<(), Error<E>> {
+        const GPIO2SEL: u8 = 1 << 3;
+        const GPIO2DIR: u8 = 1 << 6;
+        let val = self.read_register(0x04)?;
+        // the DRDY signal requires GPIO2 to be configured as output
+        let val = if state { (val | GPIO2SEL) & !GPIO2DIR } else { val & !GPIO2SEL };
+        self.write_register(0x04, val)
+    }
+
+    /// Read the level of a general purpose IO pin configured as input
+    pub fn read_gpio_input(&mut self, pin: Gpio) -> Result<bool, Error<E>> {
+        self.read_register(0x04).map(|val| (val >> (pin as u8)) & 0b1 == 1)
+    }
+
+    /// Change the baud rate: `reconfigure` changes the baud rate of the UART handler, afterwards the device
+    /// is resynchronized to the new baud rate with `synchronize()`
+    pub fn change_baud_rate(&mut self, reconfigure: impl FnOnce(&mut UART)) -> Result<(), Error<E>> {
+        reconfigure(&mut self.bus.serial);
+        self.synchronize()
+    }
+
+    /// Set the number of polls after which a read from the UART times out with `Error::Timeout`.
+    /// `None` (default) blocks until the data is received.
+    pub fn set_read_timeout(&mut self, polls: Option<u32>) {
+        self.bus.read_timeout = polls;
     }
 }
 
@@ -128,54 +771,340 @@ impl<BUS, E> ADS122x04<BUS>
     where
         BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
 {
+    /// Set the retry policy applied to transient communication errors
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// run a bus transaction, retrying it on communication errors according to the retry policy
+    fn with_retry<T>(&mut self, mut transaction: impl FnMut(&mut BUS) -> Result<T, Error<E>>) -> Result<T, Error<E>> {
+        let policy = self.retry_policy;
+        let mut attempt = 1;
+        loop {
+            match transaction(&mut self.bus) {
+                Err(Error::CommError(_)) if attempt < policy.attempts => {
+                    #[cfg(feature = "log")]
+                    log::debug!("communication error, retrying (attempt {} of {})", attempt + 1, policy.attempts);
+                    for _ in 0..policy.backoff {
+                        core::hint::spin_loop();
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Set a hook that is called for every successful bus transaction, e.g. to debug protocol-level problems
+    pub fn set_trace_hook(&mut self, hook: Option<fn(&Transaction)>) {
+        self.trace_hook = hook;
+    }
+
+    /// Set a monotonic clock used to timestamp the conversion results of the streaming and scan subsystems
+    pub fn set_clock(&mut self, clock: Option<fn() -> u64>) {
+        self.clock = clock;
+    }
+
+    /// current time of the clock, if set
+    pub(crate) fn timestamp(&self) -> Option<u64> {
+        self.clock.map(|clock| clock())
+    }
+
+    /// report a transaction to the trace hook and the logger
+    fn trace(&self, transaction: Transaction) {
+        #[cfg(feature = "log")]
+        match transaction {
+            Transaction::WriteRegister { .. } | Transaction::Command(_) => log::debug!("{:?}", transaction),
+            _ => log::trace!("{:?}", transaction),
+        }
+        if let Some(hook) = self.trace_hook {
+            hook(&transaction);
+        }
+    }
+
+    /// write a register on the bus
+    fn write_register(&mut self, reg: u8, val: u8) -> Result<(), Error<E>> {
+        self.with_retry(|bus| bus.write_register(reg, val))?;
+        self.trace(Transaction::WriteRegister { register: reg, value: val });
+        Ok(())
+    }
+
+    /// read a register on the bus
+    fn read_register(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        let val = self.with_retry(|bus| bus.read_register(reg))?;
+        self.trace(Transaction::ReadRegister { register: reg, value: val });
+        Ok(val)
+    }
+
+    /// read the conversion data on the bus
+    fn read_data(&mut self) -> Result<u32, Error<E>> {
+        self.read_data_frame().map(|frame| frame.data)
+    }
+
+    /// read the conversion data frame on the bus, including the data counter and the CRC if enabled,
+    /// in a single transaction
+    fn read_data_frame(&mut self) -> Result<DataFrame, Error<E>> {
+        let (counter, crc) = (self.config.data_counter_enable, self.config.crc);
+        let frame = if !counter && matches!(crc, Crc::Disabled) {
+            let data = self.with_retry(|bus| bus.read_data())?;
+            DataFrame { counter: None, data }
+        } else {
+            let mut buffer = [0; MAX_DATA_FRAME_LEN];
+            let frame = &mut buffer[..data_frame_len(counter, crc)];
+            self.with_retry(|bus| bus.read_frame(frame))?;
+            decode_data_frame(frame, counter, crc)?
+        };
+        self.trace(Transaction::ReadData { value: frame.data });
+        self.last_counter = frame.counter;
+        Ok(frame)
+    }
+
+    /// send a command on the bus
+    fn write_command(&mut self, command: Commands) -> Result<(), Error<E>> {
+        self.with_retry(|bus| bus.write_data(command as u8))?;
+        self.trace(Transaction::Command(command));
+        Ok(())
+    }
+
     /// updates a specified config register
+    /// The write is skipped if the register already holds the value.
     fn update_reg(&mut self, reg: u8) -> Result<(), Error<E>> {
-        match reg {
-            0x00 => {
-                let val = (self.pga_bypass as u8) | ((self.gain as u8) << 1) | ((self.mux as u8) << 4);
-                self.bus.write_register(0x00, val)
-            }
-            0x01 => {
-                let val = (self.temperature_sensor_mode as u8)
-                    | (self.v_ref.to_val() << 1)
-                    | ((self.conversion_mode as u8) << 3)
-                    | ((self.turbo_mode as u8) << 4)
-                    | ((self.data_rate as u8) << 5);
-                self.bus.write_register(0x01, val)
-            }
-            0x02 => {
-                let val = (self.current_source as u8)
-                    | ((self.burn_out_current_sources as u8) << 3)
-                    | ((self.crc as u8) << 4)
-                    | ((self.data_counter_enable as u8) << 6);
-                self.bus.write_register(0x02, val)
-            }
-            0x03 => {
-                let val = ((self.current_route_2 as u8) << 2) | ((self.current_route_1 as u8) << 5);
-                self.bus.write_register(0x03, val)
-            }
-            _ => Err(Error::InvalidValue),
+        let val = self.config.encode(reg).ok_or(Error::InvalidValue)?;
+        self.write_reg_value(reg, val)
+    }
+
+    /// write a value of the cached configuration to a config register, skipped if the register already holds it
+    fn write_reg_value(&mut self, reg: u8, val: u8) -> Result<(), Error<E>> {
+        #[cfg(feature = "float")]
+        {
+            self.lsb_voltage = self.config.lsb_voltage();
+            self.reference_microvolts = self.config.v_ref.to_microvolts() as u32;
+        }
+        if self.written[reg as usize] == Some(val) {
+            return Ok(());
+        }
+        let previous = self.written[reg as usize];
+        self.write_register(reg, val)?;
+        self.written[reg as usize] = Some(val);
+        self.track_settling(reg, previous, val);
+        if self.auto_restart && self.restart_required() {
+            self.start()?;
         }
+        Ok(())
+    }
+
+    /// Track configuration changes after which the next conversion has not settled:
+    /// a MUX or gain change while converting continuously (the running conversion started with the old setting),
+    /// and changes of the reference, the excitation or burnout current sources, which need time to settle.
+    /// Conversions at the highest data rate take longer than the start-up time of the current sources,
+    /// so by default discarding a single conversion is sufficient, see `set_settling_discards()`.
+    fn track_settling(&mut self, reg: u8, previous: Option<u8>, val: u8) {
+        let mask = match reg {
+            0x00 if matches!(self.config.conversion_mode, ConversionMode::Continuous) => 0xFF,
+            0x01 => 0b0000_0110,
+            0x02 => 0b0000_1111,
+            0x03 => 0xFF,
+            _ => 0x00,
+        };
+        let changed = previous.is_none_or(|previous| (previous ^ val) & mask != 0);
+        if mask != 0 && changed {
+            self.unsettled = self.settling_discards;
+        }
+    }
+
+    /// conversions are running continuously, so configuration changes only take effect cleanly after a restart
+    fn restart_required(&self) -> bool {
+        matches!(self.config.conversion_mode, ConversionMode::Continuous) && !self.powered_down
+    }
+
+    /// Re-issue START/SYNC after every configuration change in continuous conversion mode, so the next
+    /// conversion uses the new configuration instead of returning one stale sample
+    pub fn set_auto_restart(&mut self, state: bool) {
+        self.auto_restart = state;
+    }
+
+    /// Write any configuration changes that have not reached the device yet and restart the conversions
+    /// in continuous conversion mode
+    pub fn apply_and_restart(&mut self) -> Result<(), Error<E>> {
+        let auto_restart = core::mem::replace(&mut self.auto_restart, false);
+        let result = (0x00..=0x03).try_for_each(|reg| self.update_reg(reg));
+        self.auto_restart = auto_restart;
+        result?;
+        if self.restart_required() {
+            self.start()?;
+        }
+        Ok(())
     }
 
     /// reads a specified config register
     fn read_reg(&mut self, reg: u8) -> Result<u8, Error<E>> {
         match reg {
-            0x00 => self.bus.read_register(0x00),
-            0x01 => self.bus.read_register(0x01),
-            0x02 => self.bus.read_register(0x02),
-            0x03 => self.bus.read_register(0x03),
+            0x00 => self.read_register(0x00),
+            0x01 => self.read_register(0x01),
+            0x02 => self.read_register(0x02),
+            0x03 => self.read_register(0x03),
             _ => Err(Error::InvalidValue),
         }
     }
 
+    /// write the cached configuration to all configuration registers, including unchanged ones
+    fn write_config(&mut self) -> Result<(), Error<E>> {
+        self.written = [None; 4];
+        for reg in 0x00..=0x03 {
+            self.update_reg(reg)?;
+        }
+        Ok(())
+    }
+
+    /// The cached configuration of the device
+    pub fn config(&self) -> DeviceConfig {
+        self.config
+    }
+
+    /// apply the given configuration, only the registers whose value changes are written.
+    /// Returns `Error::ConfigConflict` without writing anything if the configuration is inconsistent.
+    fn apply_config(&mut self, config: DeviceConfig) -> Result<(), Error<E>> {
+        config.validate()?;
+        self.config = config;
+        for reg in 0x00..=0x03 {
+            self.update_reg(reg)?;
+        }
+        Ok(())
+    }
+
+    /// Set the number of conversions discarded after a configuration change that needs settling (default 1):
+    /// MUX or gain changes in continuous mode and changes of the reference or the current sources.
+    /// Increase it when external input filters need more time to settle, 0 disables the discarding.
+    pub fn set_settling_discards(&mut self, count: u8) {
+        self.settling_discards = count;
+    }
+
+    /// Discard at least the next `count` conversions, e.g. after switching a channel behind an external input filter.
+    /// Pending discards required by the settling tracking are kept.
+    pub(crate) fn discard_next(&mut self, count: u8) {
+        self.unsettled = self.unsettled.max(count);
+    }
+
+    /// Read and discard the ready conversion if it has not settled, returns whether it was discarded
+    pub(crate) fn discard_ready_unsettled(&mut self) -> Result<bool, Error<E>> {
+        if self.unsettled == 0 {
+            return Ok(false);
+        }
+        self.unsettled -= 1;
+        self.read_data()?;
+        Ok(true)
+    }
+
+    /// Apply a configuration profile, writing its register image evaluated at compile time.
+    /// Only the registers whose value changes are written, the settling discard count of the profile is applied.
+    /// Fails to compile if the configuration of the profile has conflicting settings, see `DeviceConfig::conflict()`.
+    pub fn apply_profile<P: ConfigProfile>(&mut self) -> Result<(), Error<E>> {
+        const { assert!(P::CONFIG.conflict().is_none(), "the profile configuration has conflicting settings") };
+        self.settling_discards = P::SETTLING_DISCARDS;
+        self.config = P::CONFIG;
+        for (reg, val) in P::REGISTERS.into_iter().enumerate() {
+            self.write_reg_value(reg as u8, val)?;
+        }
+        Ok(())
+    }
+
+    /// Stage multiple configuration changes and apply them with the minimal number of register writes, e.g.
+    /// `adc.configure(|c| { c.gain(Gain::Gain16).mux(Mux::Ain2Ain3); })`.
+    /// In continuous conversion mode, the conversions are restarted if the configuration changed.
+    /// Returns `Error::ConfigConflict` without writing anything if the staged configuration has conflicting
+    /// settings, see `DeviceConfig::validate()`.
+    pub fn configure(&mut self, f: impl FnOnce(&mut DeviceConfig)) -> Result<(), Error<E>> {
+        let previous = self.config;
+        let mut config = previous;
+        f(&mut config);
+        // restart once after all registers are written
+        let auto_restart = core::mem::replace(&mut self.auto_restart, false);
+        let result = self.apply_config(config);
+        self.auto_restart = auto_restart;
+        result?;
+        let changed = (0x00..=0x03).any(|reg| config.encode(reg) != previous.encode(reg));
+        if changed && self.restart_required() {
+            self.start()?;
+        }
+        Ok(())
+    }
+
+    /// Apply a temporary configuration (e.g. the AVDD monitor or the temperature sensor), run `f` and restore
+    /// the previous configuration afterwards, also if `f` returns an error.
+    /// Returns `Error::ConfigConflict` without running `f` if `config` has conflicting settings.
+    pub fn with_temporary_config<T>(
+        &mut self,
+        config: DeviceConfig,
+        f: impl FnOnce(&mut Self) -> Result<T, Error<E>>,
+    ) -> Result<T, Error<E>> {
+        let previous = self.config;
+        let result = self.apply_config(config).and_then(|_| f(self));
+        let restored = self.apply_config(previous);
+        let value = result?;
+        restored.map(|_| value)
+    }
+
+    /// Bring up the device: reset it, write the complete cached configuration and verify it by readback.
+    /// Communication errors are returned immediately, registers that do not hold the written value are
+    /// reported in the `InitReport`.
+    pub fn init<D: DelayNs>(&mut self, delay: &mut D) -> Result<InitReport, Error<E>> {
+        let config = self.config;
+        self.reset(delay)?;
+        self.config = config;
+        self.write_config()?;
+        let mut registers = [RegisterCheck { expected: 0, readback: 0 }; 4];
+        for (reg, (check, mask)) in registers.iter_mut().zip(REGISTER_MASKS).enumerate() {
+            check.expected = self.config.encode(reg as u8).ok_or(Error::InvalidValue)? & mask;
+            check.readback = self.read_reg(reg as u8)? & mask;
+        }
+        Ok(InitReport { registers })
+    }
+
+    /// Reset the device, write and verify `config` and start the conversions in continuous mode
+    fn bring_up<D: DelayNs>(&mut self, config: DeviceConfig, delay: &mut D) -> Result<(), Error<E>> {
+        config.validate()?;
+        self.config = config;
+        if !self.init(delay)?.is_ok() {
+            return Err(Error::ConfigConflict("configuration registers do not read back the written values"));
+        }
+        if matches!(self.config.conversion_mode, ConversionMode::Continuous) {
+            self.start()?;
+        }
+        Ok(())
+    }
+
+    /// Compare the configuration registers of the device with the cached configuration.
+    /// Returns `Error::DeviceResetDetected` if the registers fell back to their reset defaults (e.g. after a
+    /// brown-out) and `Error::ConfigConflict` if they differ otherwise.
+    /// With `reapply`, the cached configuration is written to the device before the error is returned.
+    pub fn check_device_health(&mut self, reapply: bool) -> Result<(), Error<E>> {
+        let mut matches = true;
+        let mut defaults = true;
+        for (reg, mask) in REGISTER_MASKS.iter().enumerate() {
+            let val = self.read_reg(reg as u8)? & mask;
+            let expected = self.config.encode(reg as u8).ok_or(Error::InvalidValue)? & mask;
+            matches &= val == expected;
+            defaults &= val == 0;
+        }
+        if matches {
+            return Ok(());
+        }
+        if reapply {
+            self.write_config()?;
+        }
+        if defaults {
+            Err(Error::DeviceResetDetected)
+        } else {
+            Err(Error::ConfigConflict("configuration registers differ from the cached configuration"))
+        }
+    }
+
     /// Calibrate the offset (according to 8.3.11 Offset Calibration in datasheet)
     /// This is recommended upon startup and after changing the gain.
     pub fn calibrate_offset(&mut self) -> Result<(), Error<E>> {
         const NUM_AVG: usize = 10;
-        let timeout = 1000;
         // short the inputs to mid-supply (AVDD + AVSS) / 2
-        let previous_mux = self.mux;
+        let previous_mux = self.config.mux;
         self.set_input_mux(Mux::Shorted)?;
         self.set_data_rate(DataRate::Sps40Turbo)?;
         self.set_conversion_mode(ConversionMode::SingleShot)?;
@@ -183,16 +1112,8 @@ impl<BUS, E> ADS122x04<BUS>
         self.offset = 0;
         // take multiple readings and average
         let mut offset = 0;
-        let mut timeout_counter = 0;
         for _ in 0..NUM_AVG {
-            self.start()?;
-            while !self.get_data_ready()? {
-                timeout_counter += 1;
-                if timeout_counter > timeout {
-                    return Err(Error::Timeout);
-                }
-            }
-            offset += self.get_raw_adc()?;
+            offset += self.measure()?;
         }
         // store offset
         self.offset = offset / (NUM_AVG as i32);
@@ -201,202 +1122,853 @@ impl<BUS, E> ADS122x04<BUS>
         Ok(())
     }
 
-    /// Enable or disable the programmable gain amplifier (PGA)
+    /// Enable or disable the programmable gain amplifier (PGA).
+    /// Returns `Error::ConfigConflict` when bypassing the PGA at a gain above 4, which requires the PGA.
     pub fn set_pga_bypass(&mut self, state: bool) -> Result<(), Error<E>> {
-        self.pga_bypass = state;
+        if state && (self.config.gain as u8) > Gain::Gain4 as u8 {
+            return Err(Error::ConfigConflict("the PGA can only be bypassed at gains 1, 2 and 4"));
+        }
+        self.config.pga_bypass = state;
         self.update_reg(0x00)
     }
 
     /// Read the status of the programmable gain amplifier (PGA)
     pub fn get_pga_bypass(&mut self) -> Result<bool, Error<E>> {
-        self.read_reg(0x00).map(|val| (val & 0b1) == 1)
+        self.read_reg(0x00).map(|val| decode_reg0(val).pga_bypass)
     }
 
-    /// Set the gain as either 0, 1, 2, 4, 8, 16, 32, 64 or 128
+    /// Set the gain as either 1, 2, 4, 8, 16, 32, 64 or 128.
+    /// Returns `Error::ConfigConflict` for gains above 4 while the PGA is bypassed, enable the PGA first.
     pub fn set_gain(&mut self, gain: Gain) -> Result<(), Error<E>> {
-        self.gain = gain;
+        if self.config.pga_bypass && (gain as u8) > Gain::Gain4 as u8 {
+            return Err(Error::ConfigConflict("gains above 4 require the PGA, which is bypassed"));
+        }
+        self.config.gain = gain;
         self.update_reg(0x00)
     }
 
+    #[cfg(feature = "float")]
+    /// Check whether the input voltages (V, referred to AVSS) keep the input stage in its linear region with the
+    /// cached gain and PGA setting, see `DeviceConfig::check_common_mode()`
+    pub fn check_common_mode(&self, vin_p: f32, vin_n: f32, avdd: f32) -> CommonModeCheck {
+        self.config.check_common_mode(vin_p, vin_n, avdd)
+    }
+
     /// Read the gain value
     pub fn get_gain(&mut self) -> Result<Gain, Error<E>> {
-        self.read_reg(0x00).map(|val| Gain::from((val >> 1) & 0b111))
+        self.read_reg(0x00).map(|val| decode_reg0(val).gain)
     }
 
     /// Set the input multiplexer (MUX)
     pub fn set_input_mux(&mut self, mux: Mux) -> Result<(), Error<E>> {
-        self.mux = mux;
+        self.config.mux = mux;
         self.update_reg(0x00)
     }
 
     /// Read the input multiplexer (MUX) setting
     pub fn get_input_mux(&mut self) -> Result<u8, Error<E>> {
-        self.read_reg(0x00).map(|val| val >> 4)
+        self.read_reg(0x00).map(|val| decode_reg0(val).mux as u8)
     }
 
     /// Enable or disable temperature sensor mode (TS)
     pub fn set_temperature_sensor_mode(&mut self, state: bool) -> Result<(), Error<E>> {
-        self.temperature_sensor_mode = state;
+        self.config.temperature_sensor_mode = state;
         self.update_reg(0x01)
     }
 
     /// Read the temperature sensor mode (TS)
     pub fn get_temperature_sensor_mode(&mut self) -> Result<bool, Error<E>> {
-        self.read_reg(0x01).map(|val| (val & 0b1) == 1)
+        self.read_reg(0x01).map(|val| decode_reg1(val).temperature_sensor_mode)
     }
 
-    /// Set the voltage reference (VREF)
+    /// Set the voltage reference (VREF).
+    /// With the reference fallback enabled, an external or supply reference is checked with `check_reference()`.
     pub fn set_vref(&mut self, v_ref: VRef) -> Result<(), Error<E>> {
-        self.v_ref = v_ref;
-        self.update_reg(0x01)
+        self.config.v_ref = v_ref;
+        self.reference_microvolts = v_ref.to_microvolts() as u32;
+        self.update_reg(0x01)?;
+        #[cfg(feature = "float")]
+        {
+            self.reference_fallback_active = false;
+            if self.reference_fallback {
+                self.check_reference()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the voltage reference (VREF) like `set_vref()`, with the voltage of an external or supply reference given
+    /// in uV, so `get_microvolts()` needs no floating-point math. The voltage stored in `v_ref` is ignored.
+    pub fn set_vref_microvolts(&mut self, v_ref: VRef, microvolts: u32) -> Result<(), Error<E>> {
+        #[cfg(feature = "float")]
+        let v_ref = VRef::from(v_ref.to_val(), microvolts as f32 / 1_000_000.0);
+        self.config.v_ref = v_ref;
+        self.reference_microvolts = microvolts;
+        self.update_reg(0x01)?;
+        #[cfg(feature = "float")]
+        {
+            self.reference_fallback_active = false;
+            if self.reference_fallback {
+                self.check_reference()?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "float")]
+    /// Fall back to the internal 2.048 V reference when `check_reference()` finds the configured reference
+    /// missing or out of range, so the data collection degrades gracefully instead of returning nonsense
+    pub fn set_reference_fallback(&mut self, state: bool) {
+        self.reference_fallback = state;
+    }
+
+    #[cfg(feature = "float")]
+    /// The device fell back to the internal reference, cleared by `set_vref()`
+    pub fn reference_fallback_active(&self) -> bool {
+        self.reference_fallback_active
+    }
+
+    #[cfg(feature = "float")]
+    /// Check the configured reference against the internal reference: an external reference with the VREF
+    /// monitor, the analog supply with the AVDD monitor. Returns `false` if the measured voltage deviates more
+    /// than 2 % from the configured voltage, in which case the internal reference is selected if the reference
+    /// fallback is enabled. The internal reference is always reported as valid.
+    pub fn check_reference(&mut self) -> Result<bool, Error<E>> {
+        let measured = match self.config.v_ref {
+            VRef::Internal => return Ok(true),
+            VRef::External(_) => self.read_reference_voltage()?,
+            VRef::AnalogSupply(_) => self.read_supply_voltage()?,
+        };
+        let expected = self.config.v_ref.to_voltage();
+        if libm::fabsf(measured - expected) <= expected * REFERENCE_TOLERANCE {
+            return Ok(true);
+        }
+        if self.reference_fallback {
+            self.config.v_ref = VRef::Internal;
+            self.update_reg(0x01)?;
+            self.reference_fallback_active = true;
+        }
+        Ok(false)
     }
 
     /// Read the voltage reference (VREF)
     pub fn get_vref(&mut self) -> Result<VRef, Error<E>> {
         self.read_reg(0x01)
-            .map(|val| VRef::from((val >> 1) & 0b11, self.v_ref.to_voltage()))
+            .map(|val| VRef::from(decode_reg1(val).v_ref, self.config.v_ref.to_voltage()))
     }
 
     /// Set the conversion mode (CM)
     pub fn set_conversion_mode(&mut self, mode: ConversionMode) -> Result<(), Error<E>> {
-        self.conversion_mode = mode;
+        #[cfg(feature = "log")]
+        log::debug!("conversion mode {:?}", mode);
+        self.config.conversion_mode = mode;
         self.update_reg(0x01)
     }
 
     /// Read the conversion mode (CM)
     pub fn get_conversion_mode(&mut self) -> Result<ConversionMode, Error<E>> {
-        self.read_reg(0x01)
-            .map(|val| ConversionMode::from((val >> 3) & 0b1))
+        self.read_reg(0x01).map(|val| decode_reg1(val).conversion_mode)
+    }
+
+    /// Set the operating mode (MODE), keeping the DR setting of the data rate.
+    /// Switching to turbo mode doubles the data rate, e.g. 20 SPS normal becomes 40 SPS turbo.
+    pub fn set_operating_mode(&mut self, mode: OperatingMode) -> Result<(), Error<E>> {
+        self.set_data_rate(self.config.data_rate.with_operating_mode(mode))
     }
 
-    /// Read the operating mode
-    pub fn get_operating_mode(&mut self) -> Result<bool, Error<E>> {
-        self.read_reg(0x01).map(|val| ((val >> 4) & 0b1) == 1)
+    /// Read the operating mode (MODE)
+    pub fn get_operating_mode(&mut self) -> Result<OperatingMode, Error<E>> {
+        self.read_reg(0x01).map(|val| decode_reg1(val).data_rate.operating_mode())
     }
 
     /// Set the data rate
     pub fn set_data_rate(&mut self, rate: DataRate) -> Result<(), Error<E>> {
-        self.data_rate = rate;
-        self.turbo_mode = (self.data_rate as u8 & 0b1) == 1;
+        #[cfg(feature = "log")]
+        log::debug!("data rate {:?}", rate);
+        self.config.data_rate = rate;
         self.update_reg(0x01)
     }
 
     /// Read the data rate
     pub fn get_data_rate(&mut self) -> Result<DataRate, Error<E>> {
-        self.read_reg(0x01)
-            .map(|val| DataRate::from((val >> 4) & 0b1111))
+        self.read_reg(0x01).map(|val| decode_reg1(val).data_rate)
+    }
+
+    /// Select the data rate whose digital filter rejects the given mains frequency
+    pub fn configure_for_line_rejection(&mut self, frequency: LineFrequency) -> Result<(), Error<E>> {
+        self.set_data_rate(frequency.data_rate())
     }
 
     /// Set the current level of the internal excitation current sources
     pub fn set_current_level(&mut self, current: CurrentSource) -> Result<(), Error<E>> {
-        self.current_source = current;
+        self.config.current_source = current;
         self.update_reg(0x02)
     }
 
     /// Read the current level of the internal excitation current sources
     pub fn get_current_level(&mut self) -> Result<CurrentSource, Error<E>> {
-        self.read_reg(0x02)
-            .map(|val| CurrentSource::from(val & 0b111))
+        self.read_reg(0x02).map(|val| decode_reg2(val).current_source)
     }
 
     /// Enable or disable the 10 uA burnout current sources
     pub fn set_burnout_current_source(&mut self, state: bool) -> Result<(), Error<E>> {
-        self.burn_out_current_sources = state;
+        self.config.burn_out_current_sources = state;
         self.update_reg(0x02)
     }
 
     /// Read the state of the 10 uA burnout current sources
     pub fn get_burnout_current_source(&mut self) -> Result<bool, Error<E>> {
-        self.read_reg(0x02).map(|val| ((val >> 3) & 0b1) == 1)
+        self.read_reg(0x02).map(|val| decode_reg2(val).burn_out_current_sources)
+    }
+
+    /// Detect an open or shorted sensor: enables the 10 uA burnout current sources, takes a reading,
+    /// checks for near full-scale saturation and restores the previous burnout state.
+    pub fn detect_open_sensor(&mut self) -> Result<SensorStatus, Error<E>> {
+        let previous_state = self.config.burn_out_current_sources;
+        self.set_burnout_current_source(true)?;
+        let reading = self.measure();
+        self.set_burnout_current_source(previous_state)?;
+        reading.map(sensor_status)
+    }
+
+    /// Set the cadence of the burnout checks of `read_with_burnout_check()`: a check is done before the first
+    /// read and then every `interval` reads, 0 disables the checks. Defaults to 1, checking before every read.
+    pub fn set_burnout_check_interval(&mut self, interval: u32) {
+        self.burnout_check_interval = interval;
+        self.burnout_countdown = 0;
+    }
+
+    /// Take a measurement annotated with the sensor status, for long-running loggers to detect failed sensors
+    /// in-band. When a check is due (see `set_burnout_check_interval()`), the burnout current sources are
+    /// enabled for one conversion before the measurement, as `detect_open_sensor()` does; the measurement itself
+    /// is taken after the inputs settled with the sources in their configured state.
+    pub fn read_with_burnout_check(&mut self) -> Result<BurnoutCheckedResult, Error<E>> {
+        let checked = self.burnout_check_interval > 0 && self.burnout_countdown == 0;
+        if checked {
+            self.sensor_status = self.detect_open_sensor()?;
+            self.burnout_countdown = self.burnout_check_interval;
+        }
+        self.burnout_countdown = self.burnout_countdown.saturating_sub(1);
+        self.wait_for_conversion()?;
+        let result = self.read_conversion();
+        if self.auto_power_down {
+            self.power_down()?;
+        }
+        Ok(BurnoutCheckedResult {
+            result: result?,
+            sensor: self.sensor_status,
+            checked,
+        })
+    }
+
+    #[cfg(feature = "float")]
+    /// Measure the reference voltage (V) with the VREF monitor against the internal reference
+    pub fn read_reference_voltage(&mut self) -> Result<f32, Error<E>> {
+        self.read_monitor(Mux::VrefMonitor)
+    }
+
+    #[cfg(feature = "float")]
+    /// Measure the analog supply voltage AVDD - AVSS (V) with the AVDD monitor against the internal reference
+    pub fn read_supply_voltage(&mut self) -> Result<f32, Error<E>> {
+        self.read_monitor(Mux::AvddMonitor)
+    }
+
+    #[cfg(feature = "float")]
+    /// Set the alarm thresholds of the reference voltage checked by `check_rails()`, `None` disables the alarm.
+    /// Returns `Error::InvalidValue` if the minimum exceeds the maximum.
+    pub fn set_reference_limits(&mut self, limits: Option<RailLimits>) -> Result<(), Error<E>> {
+        if limits.is_some_and(|limits| limits.min > limits.max) {
+            return Err(Error::InvalidValue);
+        }
+        self.reference_limits = limits;
+        Ok(())
+    }
+
+    #[cfg(feature = "float")]
+    /// Set the alarm thresholds of the analog supply voltage checked by `check_rails()`, `None` disables the alarm.
+    /// Returns `Error::InvalidValue` if the minimum exceeds the maximum.
+    pub fn set_supply_limits(&mut self, limits: Option<RailLimits>) -> Result<(), Error<E>> {
+        if limits.is_some_and(|limits| limits.min > limits.max) {
+            return Err(Error::InvalidValue);
+        }
+        self.supply_limits = limits;
+        Ok(())
+    }
+
+    #[cfg(feature = "float")]
+    /// Measure the reference and the analog supply voltage with the VREF and AVDD monitors and check them against
+    /// the limits set with `set_reference_limits()` and `set_supply_limits()`. Intended to be called periodically
+    /// by supervisory code, the cached configuration is restored afterwards.
+    pub fn check_rails(&mut self) -> Result<RailsReport, Error<E>> {
+        let reference_voltage = self.read_reference_voltage()?;
+        let supply_voltage = self.read_supply_voltage()?;
+        Ok(RailsReport {
+            reference_voltage,
+            supply_voltage,
+            reference: self.reference_limits.map_or(RailAlarm::Ok, |limits| limits.check(reference_voltage)),
+            supply: self.supply_limits.map_or(RailAlarm::Ok, |limits| limits.check(supply_voltage)),
+        })
+    }
+
+    #[cfg(feature = "float")]
+    /// measure one of the monitors, which divide the voltage by 4, with the cached configuration restored afterwards
+    fn read_monitor(&mut self, mux: Mux) -> Result<f32, Error<E>> {
+        let mut config = self.config;
+        config
+            .mux(mux)
+            .gain(Gain::Gain1)
+            .v_ref(VRef::Internal)
+            .conversion_mode(ConversionMode::SingleShot)
+            .temperature_sensor_mode(false);
+        self.with_temporary_config(config, |adc| {
+            let raw = adc.measure()?;
+            Ok(adc.convert_raw_to_voltage(raw) * MONITOR_DIVIDER)
+        })
+    }
+
+    #[cfg(feature = "float")]
+    /// Run the self-test suite for production test and field troubleshooting: shorted-input offset, VREF and
+    /// AVDD monitors, open/shorted sensor detection with the burnout current sources on the configured input
+    /// and the internal temperature. The cached configuration is restored afterwards.
+    /// Communication errors are returned immediately, failed checks are reported in the `DiagnosticsReport`.
+    pub fn self_test<D: DelayNs>(&mut self, delay: &mut D) -> Result<DiagnosticsReport, Error<E>> {
+        let mut config = self.config;
+        config
+            .conversion_mode(ConversionMode::SingleShot)
+            .temperature_sensor_mode(false);
+
+        let mut shorted = config;
+        shorted.mux(Mux::Shorted);
+        let offset = self.with_temporary_config(shorted, |adc| Ok(adc.measure()? + adc.offset))?;
+
+        let reference_voltage = self.read_reference_voltage()?;
+        let supply_voltage = self.read_supply_voltage()?;
+
+        let mut burnout = config;
+        burnout.burnout_current_source(true);
+        let sensor = self.with_temporary_config(burnout, |adc| {
+            delay.delay_us(BURNOUT_SETTLING_US);
+            adc.measure().map(sensor_status)
+        })?;
+
+        let temperature = self.measure_temperature()?;
+
+        let expected_reference = self.config.v_ref.to_voltage();
+        Ok(DiagnosticsReport {
+            offset,
+            reference_voltage,
+            supply_voltage,
+            sensor,
+            temperature,
+            offset_ok: offset.abs() <= SELF_TEST_MAX_OFFSET,
+            reference_ok: libm::fabsf(reference_voltage - expected_reference)
+                <= expected_reference * REFERENCE_TOLERANCE,
+            supply_ok: (2.3..=5.5).contains(&supply_voltage),
+            temperature_ok: (-40.0..=125.0).contains(&temperature),
+        })
     }
 
     /// Set the CRC mode
     pub fn set_crc(&mut self, crc: Crc) -> Result<(), Error<E>> {
-        self.crc = crc;
+        self.config.crc = crc;
         self.update_reg(0x02)
     }
 
     /// Read the CRC mode
     pub fn get_crc(&mut self) -> Result<Crc, Error<E>> {
-        self.read_reg(0x02).map(|val| Crc::from((val >> 4) & 0b11))
+        self.read_reg(0x02).map(|val| decode_reg2(val).crc)
     }
 
     /// Enable or disable data counter
     pub fn set_data_counter(&mut self, state: bool) -> Result<(), Error<E>> {
-        self.data_counter_enable = state;
+        self.config.data_counter_enable = state;
         self.update_reg(0x02)
     }
 
     /// Read the state of the data counter
     pub fn get_data_counter(&mut self) -> Result<bool, Error<E>> {
-        self.read_reg(0x02).map(|val| ((val >> 6) & 0b1) == 1)
+        self.read_reg(0x02).map(|val| decode_reg2(val).data_counter_enable)
     }
 
     /// Read the data ready (DRDY) register
     pub fn get_data_ready(&mut self) -> Result<bool, Error<E>> {
-        self.read_reg(0x02).map(|val| ((val >> 7) & 0b1) == 1)
+        self.read_reg(0x02).map(|val| decode_reg2(val).data_ready)
+    }
+
+    /// Read the conversion status from configuration registers 1 and 2 of the device: the data ready flag and
+    /// the conversion and operating modes are decoded from the registers, the data counter is the one of the
+    /// last conversion read, if the data counter is enabled on the device.
+    /// Returns `Error::DeviceResetDetected` if both registers fell back to their reset defaults while the cached
+    /// configuration differs (e.g. after a brown-out), see `check_device_health()` to compare all registers.
+    pub fn status(&mut self) -> Result<DeviceStatus, Error<E>> {
+        let reg1 = self.read_reg(0x01)?;
+        let reg2 = self.read_reg(0x02)?;
+        let expected = [
+            self.config.encode(0x01).ok_or(Error::InvalidValue)? & REGISTER_MASKS[1],
+            self.config.encode(0x02).ok_or(Error::InvalidValue)? & REGISTER_MASKS[2],
+        ];
+        if [reg1 & REGISTER_MASKS[1], reg2 & REGISTER_MASKS[2]] == [0, 0] && expected != [0, 0] {
+            return Err(Error::DeviceResetDetected);
+        }
+        let reg1 = decode_reg1(reg1);
+        let reg2 = decode_reg2(reg2);
+        Ok(DeviceStatus {
+            data_ready: reg2.data_ready,
+            conversion_mode: reg1.conversion_mode,
+            operating_mode: reg1.data_rate.operating_mode(),
+            counter: self.last_counter.filter(|_| reg2.data_counter_enable),
+        })
     }
 
     /// Set the current routing of the excitation current source 1
     pub fn set_current_route_1(&mut self, route: CurrentRoute) -> Result<(), Error<E>> {
-        self.current_route_1 = route;
+        self.config.current_route_1 = route;
         self.update_reg(0x03)
     }
 
     /// Read the current routing of the excitation current source 1
     pub fn get_current_route_1(&mut self) -> Result<CurrentRoute, Error<E>> {
-        self.read_reg(0x03)
-            .map(|val| CurrentRoute::from((val >> 5) & 0b111))
+        self.read_reg(0x03).map(|val| decode_reg3(val).current_route_1)
     }
 
     /// Set the current routing of the excitation current source 2
     pub fn set_current_route_2(&mut self, route: CurrentRoute) -> Result<(), Error<E>> {
-        self.current_route_2 = route;
+        self.config.current_route_2 = route;
         self.update_reg(0x03)
     }
 
     /// Read the current routing of the excitation current source 2
     pub fn get_current_route_2(&mut self) -> Result<CurrentRoute, Error<E>> {
-        self.read_reg(0x03)
-            .map(|val| CurrentRoute::from((val >> 3) & 0b111))
+        self.read_reg(0x03).map(|val| decode_reg3(val).current_route_2)
+    }
+
+    /// Configure an excitation current source: sets the current level and routing in one operation and
+    /// waits for the start-up time of the current source.
+    /// Note that both current sources share the same current level.
+    /// Returns `Error::ConfigConflict` if the other current source is routed to the same pin,
+    /// or if the current source is routed to the reference pins while the external reference is used.
+    pub fn configure_idac<D: DelayNs>(
+        &mut self,
+        idac: Idac,
+        route: CurrentRoute,
+        current: CurrentSource,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let other_route = match idac {
+            Idac::Idac1 => self.config.current_route_2,
+            Idac::Idac2 => self.config.current_route_1,
+        };
+        if !matches!(route, CurrentRoute::Off) && route as u8 == other_route as u8 {
+            return Err(Error::ConfigConflict("both current sources are routed to the same pin"));
+        }
+        if matches!(route, CurrentRoute::RefP | CurrentRoute::RefN)
+            && matches!(self.config.v_ref, VRef::External(_)) {
+            return Err(Error::ConfigConflict("current source routed to the external reference pins"));
+        }
+        self.config.current_source = current;
+        match idac {
+            Idac::Idac1 => self.config.current_route_1 = route,
+            Idac::Idac2 => self.config.current_route_2 = route,
+        }
+        self.update_reg(0x02)?;
+        self.update_reg(0x03)?;
+        delay.delay_us(IDAC_STARTUP_US);
+        Ok(())
+    }
+
+    /// Take a measurement with IDAC chopping: one reading with the configured routing of the excitation
+    /// current sources and one with the routings of IDAC1 and IDAC2 swapped, returning their mean.
+    /// This cancels the mismatch of the current sources and thermoelectric EMFs in 3-wire RTD measurements.
+    /// The routing is restored afterwards. Returns `Error::ConfigConflict` unless both current sources are routed.
+    pub fn measure_idac_chopped<D: DelayNs>(&mut self, delay: &mut D) -> Result<i32, Error<E>> {
+        let (route_1, route_2) = (self.config.current_route_1, self.config.current_route_2);
+        if matches!(route_1, CurrentRoute::Off) || matches!(route_2, CurrentRoute::Off) {
+            return Err(Error::ConfigConflict("IDAC chopping requires both current sources to be routed"));
+        }
+        let first = self.measure()?;
+        self.config.current_route_1 = route_2;
+        self.config.current_route_2 = route_1;
+        let swapped = self.update_reg(0x03).and_then(|_| {
+            delay.delay_us(IDAC_STARTUP_US);
+            self.measure()
+        });
+        self.config.current_route_1 = route_1;
+        self.config.current_route_2 = route_2;
+        self.update_reg(0x03)?;
+        delay.delay_us(IDAC_STARTUP_US);
+        let second = swapped?;
+        Ok(((first as i64 + second as i64) / 2) as i32)
     }
 
     /// transform the raw u32 value to signed i32 value according to datasheet
     fn raw_to_signed(&self, x: u32) -> i32 {
-        if (x & 0x00800000) == 0x00800000 {
-            (x | 0xFF000000) as i32
-        } else {
-            x as i32
-        }
+        codec::sign_extend(x)
     }
 
     /// Read the raw ADC value and subtract the offset
     pub fn get_raw_adc(&mut self) -> Result<i32, Error<E>> {
-        self.bus.read_data().map(|val| self.raw_to_signed(val) - self.offset)
+        self.read_data().map(|val| self.raw_to_signed(val) - self.offset)
+    }
+
+    /// wait until the data ready (DRDY) bit is set
+    fn wait_data_ready(&mut self) -> Result<(), Error<E>> {
+        let mut timeout_counter = 0;
+        while !self.get_data_ready()? {
+            timeout_counter += 1;
+            if timeout_counter > DATA_READY_TIMEOUT {
+                return Err(Error::Timeout);
+            }
+        }
+        Ok(())
+    }
+
+    fn wait_data_ready_pin<P: InputPin>(&self, drdy: &mut P) -> Result<(), Error<E>> {
+        let mut timeout_counter = 0;
+        while !self.is_data_ready_pin(drdy)? {
+            timeout_counter += 1;
+            if timeout_counter > DATA_READY_TIMEOUT {
+                return Err(Error::Timeout);
+            }
+            core::hint::spin_loop();
+        }
+        Ok(())
+    }
+
+    /// Check the data ready (DRDY) pin, which is driven low when new conversion data is available.
+    /// This is the DRDY pin of the ADS122C04 or GPIO2 of the ADS122U04 configured with `set_gpio2_data_ready()`.
+    pub fn is_data_ready_pin<P: InputPin>(&self, drdy: &mut P) -> Result<bool, Error<E>> {
+        drdy.is_low().map_err(|_| Error::PinError)
+    }
+
+    /// Start a conversion (in single-shot mode), wait until the data ready (DRDY) pin signals new data and
+    /// read the raw ADC value with the offset subtracted, without polling configuration register 2.
+    /// Returns `Error::Timeout` if the pin does not signal new data in time.
+    /// With auto power-down enabled, the device is powered down afterwards like with `measure()`.
+    pub fn measure_with_drdy_pin<P: InputPin>(&mut self, drdy: &mut P) -> Result<i32, Error<E>> {
+        let raw = self.discard_unsettled().and_then(|_| {
+            if matches!(self.config.conversion_mode, ConversionMode::SingleShot) || self.powered_down {
+                self.start()?;
+            }
+            self.wait_data_ready_pin(drdy)?;
+            self.get_raw_adc()
+        });
+        self.finish_measurement(raw)
+    }
+
+    /// power the device down after a measurement with auto power-down enabled, also if the measurement failed
+    fn finish_measurement(&mut self, raw: Result<i32, Error<E>>) -> Result<i32, Error<E>> {
+        if self.auto_power_down {
+            let powered_down = self.power_down();
+            let raw = raw?;
+            powered_down.map(|_| raw)
+        } else {
+            raw
+        }
+    }
+
+    /// start a conversion (in single-shot mode) and wait for the data
+    fn wait_for_conversion(&mut self) -> Result<(), Error<E>> {
+        self.discard_unsettled()?;
+        self.wait_for_next_conversion()
+    }
+
+    fn wait_for_next_conversion(&mut self) -> Result<(), Error<E>> {
+        if matches!(self.config.conversion_mode, ConversionMode::SingleShot) || self.powered_down {
+            self.start()?;
+        }
+        self.wait_data_ready()
+    }
+
+    /// discard the conversions that have not settled after a configuration change
+    fn discard_unsettled(&mut self) -> Result<(), Error<E>> {
+        while self.unsettled > 0 {
+            self.wait_for_next_conversion()?;
+            self.read_data()?;
+            self.unsettled -= 1;
+        }
+        Ok(())
+    }
+
+    /// Take a measurement: start a conversion (in single-shot mode), wait for the data and
+    /// read the raw ADC value with the offset subtracted
+    pub fn measure(&mut self) -> Result<i32, Error<E>> {
+        let raw = self.wait_for_conversion().and_then(|_| self.get_raw_adc());
+        self.finish_measurement(raw)
+    }
+
+    /// Issue START/SYNC and wait for the first conversion completed after it, in either conversion mode.
+    /// A conversion still pending from before the start is read out first, so the data ready (DRDY) bit can
+    /// only report post-start data, and conversions that have not settled after a configuration change are
+    /// discarded. With the data counter enabled (`set_data_counter()`), a result repeating the
+    /// counter of the last read conversion is additionally rejected and the next conversion awaited.
+    /// Returns `Error::Timeout` if no fresh conversion becomes available.
+    pub fn start_and_wait(&mut self) -> Result<ConversionResult, Error<E>> {
+        if self.get_data_ready()? {
+            self.read_data_frame()?;
+        }
+        self.start()?;
+        while self.unsettled > 0 {
+            self.wait_data_ready()?;
+            self.read_data()?;
+            self.unsettled -= 1;
+            if matches!(self.config.conversion_mode, ConversionMode::SingleShot) {
+                self.start()?;
+            }
+        }
+        let previous = self.last_counter;
+        for _ in 0..CONSISTENT_READ_ATTEMPTS {
+            self.wait_data_ready()?;
+            let result = self.read_conversion()?;
+            if result.counter.is_none() || result.counter != previous {
+                return Ok(result);
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Take a measurement like `measure()`, but sleep for the single-shot conversion latency of the data rate
+    /// (`DataRate::single_shot_latency_us()`) after starting the conversion instead of polling the data ready
+    /// (DRDY) bit, so the bus stays idle while converting. In continuous mode this is the same as `measure()`.
+    pub fn measure_with_delay<D: DelayNs>(&mut self, delay: &mut D) -> Result<i32, Error<E>> {
+        let raw = self.discard_unsettled().and_then(|_| {
+            if matches!(self.config.conversion_mode, ConversionMode::SingleShot) || self.powered_down {
+                self.start()?;
+                delay.delay_us(self.config.data_rate.single_shot_latency_us());
+            }
+            self.wait_data_ready()?;
+            self.get_raw_adc()
+        });
+        self.finish_measurement(raw)
+    }
+
+    /// Automatically put the device in power-down mode after every `measure()`, trading latency for idle
+    /// current. The next `measure()` wakes the device up again.
+    pub fn set_auto_power_down(&mut self, state: bool) {
+        self.auto_power_down = state;
+    }
+
+    /// Take `n` consecutive measurements and return their mean and spread
+    pub fn read_averaged(&mut self, n: u32) -> Result<AveragedReading, Error<E>> {
+        if n == 0 {
+            return Err(Error::InvalidValue);
+        }
+        let mut sum: i64 = 0;
+        let mut min = i32::MAX;
+        let mut max = i32::MIN;
+        for _ in 0..n {
+            let raw = self.measure()?;
+            sum += raw as i64;
+            min = min.min(raw);
+            max = max.max(raw);
+        }
+        Ok(AveragedReading {
+            mean: (sum / n as i64) as i32,
+            min,
+            max,
+        })
+    }
+
+    /// Take `N` consecutive measurements and combine them with the given estimator,
+    /// e.g. the median to reject single-sample spikes
+    pub fn read_estimate<const N: usize>(&mut self, estimator: Estimator) -> Result<i32, Error<E>> {
+        if N == 0 {
+            return Err(Error::InvalidValue);
+        }
+        let mut samples = [0; N];
+        for sample in samples.iter_mut() {
+            *sample = self.measure()?;
+        }
+        Ok(estimator.estimate(&mut samples))
+    }
+
+    #[cfg(feature = "float")]
+    /// Measure the internal temperature sensor in °C.
+    /// Temperature sensor mode is enabled for the measurement and restored afterwards.
+    pub fn measure_temperature(&mut self) -> Result<f32, Error<E>> {
+        let previous_state = self.config.temperature_sensor_mode;
+        self.set_temperature_sensor_mode(true)?;
+        let reading = self
+            .wait_for_conversion()
+            .and_then(|_| self.read_data());
+        self.set_temperature_sensor_mode(previous_state)?;
+        // 14-bit result, left-justified in the 24-bit data, 0.03125 °C per LSB
+        reading.map(|val| (self.raw_to_signed(val) >> 10) as f32 * 0.03125)
+    }
+
+    /// Take a measurement with automatic gain ranging: starting at the configured gain (clamped to the bounds),
+    /// the gain is stepped down for near full-scale readings and stepped up for small readings
+    /// until the reading is in range. Returns the raw ADC value together with the gain used.
+    /// With the PGA bypassed, `max_gain` must not exceed `Gain::Gain4`.
+    pub fn read_auto_gain(&mut self, min_gain: Gain, max_gain: Gain) -> Result<(i32, Gain), Error<E>> {
+        let (min, max) = (min_gain as u8, max_gain as u8);
+        if min > max {
+            return Err(Error::InvalidValue);
+        }
+        let mut gain = (self.config.gain as u8).clamp(min, max);
+        self.set_gain(Gain::from(gain))?;
+        let mut raw = self.measure()?;
+        // limit the number of steps in case the input is noisy around a threshold
+        for _ in 0..8 {
+            if raw.abs() >= AUTO_GAIN_UPPER_THRESHOLD && gain > min {
+                gain -= 1;
+            } else if raw.abs() < AUTO_GAIN_LOWER_THRESHOLD && gain < max {
+                gain += 1;
+            } else {
+                break;
+            }
+            self.set_gain(Gain::from(gain))?;
+            raw = self.measure()?;
+        }
+        Ok((raw, self.config.gain))
+    }
+
+    /// Return `Error::Saturated` from `read_conversion()` if the reading is pinned at full scale
+    pub fn set_error_on_saturation(&mut self, state: bool) {
+        self.error_on_saturation = state;
+    }
+
+    /// Read the latest conversion result including the saturation flag and the data counter.
+    /// With CRC enabled, the data is checked and `Error::CrcMismatch` returned on a mismatch.
+    pub fn read_conversion(&mut self) -> Result<ConversionResult, Error<E>> {
+        let frame = self.read_data_frame()?;
+        self.conversion_result(frame)
+    }
+
+    /// convert a data frame to a conversion result
+    fn conversion_result(&self, frame: DataFrame) -> Result<ConversionResult, Error<E>> {
+        let raw = self.raw_to_signed(frame.data);
+        let saturated = raw == 0x7FFFFF || raw == -0x800000;
+        if saturated && self.error_on_saturation {
+            return Err(Error::Saturated);
+        }
+        Ok(ConversionResult {
+            raw: raw - self.offset,
+            counter: frame.counter,
+            timestamp: self.timestamp(),
+            saturated,
+        })
     }
 
-    /// Read the voltage of the ADC
+    /// Read the latest conversion result and make sure no new conversion completed during the read, which could
+    /// tear the data in continuous conversion mode without CRC: DRDY is checked after the read and the data
+    /// is re-read if it is set again. Returns `Error::Timeout` if no consistent read succeeded.
+    pub fn read_conversion_consistent(&mut self) -> Result<ConversionResult, Error<E>> {
+        for _ in 0..CONSISTENT_READ_ATTEMPTS {
+            let result = self.read_conversion()?;
+            if !self.get_data_ready()? {
+                return Ok(result);
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    #[cfg(feature = "float")]
+    /// Read the input voltage of the ADC, corrected for the gain
     pub fn get_voltage(&mut self) -> Result<f32, Error<E>> {
         // returns voltage in V
-        let raw = self.get_raw_adc();
-        let v_ref = self.v_ref.to_voltage();
-        raw.map(|raw| (v_ref as f64 / ((1 << 23) as f64) * (raw as f64)) as f32)
+        self.get_raw_adc().map(|raw| self.convert_raw_to_voltage(raw))
     }
 
-    /// Convert the raw ADC value to voltage
+    /// Read the input voltage of the ADC in uV, corrected for the gain and computed with integer math only.
+    /// Without the `float` feature, set an external or supply reference with `set_vref_microvolts()`.
+    pub fn get_microvolts(&mut self) -> Result<i64, Error<E>> {
+        let v_ref = match self.config.v_ref {
+            VRef::Internal => INTERNAL_REFERENCE_MICROVOLTS,
+            VRef::External(_) | VRef::AnalogSupply(_) => self.reference_microvolts,
+        } as i64;
+        let gain = self.config.gain.factor() as i64;
+        self.get_raw_adc()
+            .map(|raw| raw as i64 * v_ref / ((1 << 23) * gain))
+    }
+
+    #[cfg(feature = "float")]
+    /// Convert the raw ADC value to the input voltage, corrected for the gain
     pub fn convert_raw_to_voltage(&mut self, raw: i32) -> f32 {
         // returns voltage in V
-        let v_ref = self.v_ref.to_voltage();
-        (v_ref as f64 / ((1 << 23) as f64) * (raw as f64)) as f32
+        raw as f32 * self.lsb_voltage
     }
 
-    /// Reset the device
-    pub fn reset(&mut self) -> Result<(), Error<E>> {
-        self.bus.write_data(Commands::Reset as u8)
+    #[cfg(feature = "float")]
+    /// Take a measurement (see `measure()`) and return it as a fraction of the full scale, corrected for the gain
+    /// (code / 2^23 / gain). This is the natural quantity for ratiometric setups (RTD, bridges), where the reference
+    /// voltage cancels out.
+    pub fn read_ratiometric(&mut self) -> Result<f32, Error<E>> {
+        let raw = self.measure()?;
+        Ok(self.convert_raw_to_ratio(raw))
+    }
+
+    #[cfg(feature = "float")]
+    /// Convert the raw ADC value to a fraction of the full scale, corrected for the gain
+    pub fn convert_raw_to_ratio(&self, raw: i32) -> f32 {
+        let gain = self.config.gain.factor() as f32;
+        raw as f32 / ((1 << 23) as f32) / gain
+    }
+
+    /// Resynchronize the communication interface after a glitch.
+    /// On UART, stale bytes are discarded and the 0x55 synchronization word is re-sent.
+    pub fn resync(&mut self) -> Result<(), Error<E>> {
+        self.bus.resync()
+    }
+
+    /// Synchronize the interface and verify the communication by reading back configuration register 0.
+    /// On UART, stale received bytes are discarded and the synchronization word the device derives the baud rate
+    /// from is sent first. Returns `Error::InvalidValue` if the readback does not match the last written value.
+    /// `reset()` synchronizes the interface itself.
+    pub fn synchronize(&mut self) -> Result<(), Error<E>> {
+        self.resync()?;
+        let val = self.read_register(0x00)?;
+        match self.written[0] {
+            Some(expected) if expected != val => Err(Error::InvalidValue),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reset the device, wait until it accepts commands again and reset the cached configuration to the
+    /// power-on defaults. Afterwards, the interface is synchronized and verified with `synchronize()`.
+    /// The offset calibration is kept.
+    pub fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.write_command(Commands::Reset)?;
+        delay.delay_us(RESET_DELAY_US);
+        self.reset_state();
+        self.synchronize()
+    }
+
+    /// reset the cached configuration and the conversion state to the power-on defaults after a reset command
+    fn reset_state(&mut self) {
+        self.config = DeviceConfig::default();
+        #[cfg(feature = "float")]
+        {
+            self.lsb_voltage = self.config.lsb_voltage();
+            self.reference_fallback_active = false;
+        }
+        self.written = [Some(0); 4];
+        self.last_counter = None;
+        self.oneshot_pending = false;
+        self.powered_down = false;
     }
 
     /// Start a measurement
     pub fn start(&mut self) -> Result<(), Error<E>> {
-        self.bus.write_data(Commands::StartSync as u8)
+        self.write_command(Commands::StartSync)?;
+        self.powered_down = false;
+        Ok(())
+    }
+
+    /// Re-synchronize running continuous conversions, e.g. to an excitation switching event: START/SYNC aborts the
+    /// conversion in progress and restarts the digital filter, so the conversion period is realigned to this call.
+    /// Data that became ready before the call is not discarded.
+    /// Returns `Error::ConfigConflict` in single-shot mode or while powered down, where `start()` is used instead.
+    pub fn sync(&mut self) -> Result<(), Error<E>> {
+        if !matches!(self.config.conversion_mode, ConversionMode::Continuous) || self.powered_down {
+            return Err(Error::ConfigConflict("sync() requires running continuous conversions"));
+        }
+        self.write_command(Commands::StartSync)
+    }
+
+    /// Put the device in power-down mode, it wakes up with the next `start()`
+    pub fn power_down(&mut self) -> Result<(), Error<E>> {
+        self.write_command(Commands::PowerDown)?;
+        self.powered_down = true;
+        Ok(())
     }
 }
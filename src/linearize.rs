@@ -0,0 +1,146 @@
+//! Piecewise-linear conversion of measured values to engineering units from a calibration table
+//!
+//! The table maps a measured quantity (e.g. V, mV/V or the resistance of a thermistor) to engineering units:
+//!
+//! ```ignore
+//! let table = Linearizer::new([(1000.0, 80.0), (3000.0, 40.0), (10000.0, 25.0), (30000.0, 0.0)])
+//!     .unwrap()
+//!     .with_clamping(true);
+//! let temperature = thermistor.resistance(adc.read_ratiometric()?).map(|r| table.convert(r));
+//! ```
+
+use crate::scale::{LinearScale, ScaleStatus, ScaledValue};
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Piecewise-linear mapping through `N` calibration points (input, output) with strictly increasing inputs.
+/// Inputs outside of the table are extrapolated from the first or last segment, or clamped to the end points.
+pub struct Linearizer<const N: usize> {
+    points: [(f32, f32); N],
+    clamp: bool,
+}
+
+impl<const N: usize> Linearizer<N> {
+    /// Create a linearizer from calibration points (input, output), without clamping.
+    /// Returns `None` if there are fewer than two points or the inputs are not strictly increasing.
+    pub fn new(points: [(f32, f32); N]) -> Option<Self> {
+        if N < 2 || !points.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+            return None;
+        }
+        Some(Linearizer { points, clamp: false })
+    }
+
+    /// Clamp the output to the outputs of the first and the last calibration point
+    pub fn with_clamping(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// The calibration points
+    pub fn points(&self) -> &[(f32, f32); N] {
+        &self.points
+    }
+
+    /// Convert the input to engineering units, with the position of the input relative to the table
+    pub fn apply(&self, input: f32) -> ScaledValue {
+        // index of the first point of the segment containing the input, end segments extrapolate
+        let segment = self.points[1..N - 1]
+            .iter()
+            .take_while(|point| point.0 < input)
+            .count();
+        let ((input_zero, output_zero), (input_span, output_span)) = (self.points[segment], self.points[segment + 1]);
+        // the points are strictly increasing, so the segment has a non-zero span
+        let scale = LinearScale {
+            input_zero,
+            input_span,
+            output_zero,
+            output_span,
+            clamp: false,
+        };
+        let value = scale.apply(input).value;
+        let (first, last) = (self.points[0], self.points[N - 1]);
+        if input < first.0 {
+            ScaledValue {
+                value: if self.clamp { first.1 } else { value },
+                status: ScaleStatus::UnderRange,
+            }
+        } else if input > last.0 {
+            ScaledValue {
+                value: if self.clamp { last.1 } else { value },
+                status: ScaleStatus::OverRange,
+            }
+        } else {
+            ScaledValue {
+                value,
+                status: ScaleStatus::InRange,
+            }
+        }
+    }
+
+    /// Convert the input to engineering units
+    pub fn convert(&self, input: f32) -> f32 {
+        self.apply(input).value
+    }
+}
+
+impl Linearizer<2> {
+    /// Two-point calibration mapping `input_a` to `output_a` and `input_b` to `output_b`, the points may be
+    /// given in any order. Returns `None` if both inputs are equal.
+    pub fn two_point(input_a: f32, output_a: f32, input_b: f32, output_b: f32) -> Option<Self> {
+        if input_a <= input_b {
+            Self::new([(input_a, output_a), (input_b, output_b)])
+        } else {
+            Self::new([(input_b, output_b), (input_a, output_a)])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Linearizer<4> {
+        Linearizer::new([(0.0, 0.0), (1.0, 10.0), (2.0, 30.0), (4.0, 40.0)]).unwrap()
+    }
+
+    #[test]
+    fn rejects_invalid_tables() {
+        assert!(Linearizer::new([(0.0, 1.0)]).is_none());
+        assert!(Linearizer::new([(0.0, 1.0), (0.0, 2.0)]).is_none());
+        assert!(Linearizer::new([(0.0, 1.0), (2.0, 2.0), (1.0, 3.0)]).is_none());
+        assert!(Linearizer::two_point(1.0, 5.0, 1.0, 6.0).is_none());
+    }
+
+    #[test]
+    fn selects_the_segment() {
+        let table = table();
+        for (input, output) in [(0.0, 0.0), (0.5, 5.0), (1.0, 10.0), (1.5, 20.0), (2.0, 30.0), (3.0, 35.0), (4.0, 40.0)] {
+            let scaled = table.apply(input);
+            assert_eq!(scaled.value, output, "input {}", input);
+            assert_eq!(scaled.status, ScaleStatus::InRange);
+        }
+    }
+
+    #[test]
+    fn extrapolates_or_clamps_at_both_ends() {
+        let table = table();
+        let below = table.apply(-1.0);
+        assert_eq!((below.value, below.status), (-10.0, ScaleStatus::UnderRange));
+        let above = table.apply(6.0);
+        assert_eq!((above.value, above.status), (50.0, ScaleStatus::OverRange));
+
+        let table = table.with_clamping(true);
+        let below = table.apply(-1.0);
+        assert_eq!((below.value, below.status), (0.0, ScaleStatus::UnderRange));
+        let above = table.apply(6.0);
+        assert_eq!((above.value, above.status), (40.0, ScaleStatus::OverRange));
+    }
+
+    #[test]
+    fn two_point_in_any_order() {
+        let forward = Linearizer::two_point(4.0, 0.0, 20.0, 100.0).unwrap();
+        let reverse = Linearizer::two_point(20.0, 100.0, 4.0, 0.0).unwrap();
+        assert_eq!(forward.points(), reverse.points());
+        assert_eq!(forward.convert(12.0), 50.0);
+    }
+}
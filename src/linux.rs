@@ -0,0 +1,66 @@
+//! Convenience constructors for Linux hosts such as a Raspberry Pi, available with the `linux` feature
+//!
+//! The devices of [linux-embedded-hal](https://crates.io/crates/linux-embedded-hal) are wrapped in the adapters
+//! of the `compat` module:
+//!
+//! ```ignore
+//! let mut adc = ADS122x04::open_i2c("/dev/i2c-1", 0x40)?;
+//! adc.reset(&mut linux_delay())?;
+//! println!("{}", adc.read_conversion()?);
+//! ```
+
+#[cfg(any(feature = "i2c", feature = "uart"))]
+use std::path::Path;
+
+#[cfg(feature = "i2c")]
+use linux_embedded_hal::i2cdev::linux::LinuxI2CError;
+#[cfg(feature = "i2c")]
+use linux_embedded_hal::I2cdev;
+#[cfg(feature = "uart")]
+use linux_embedded_hal::Serial;
+use linux_embedded_hal::Delay;
+
+#[cfg(feature = "i2c")]
+use crate::compat::I2cCompat;
+#[cfg(feature = "uart")]
+use crate::compat::SerialCompat;
+use crate::compat::DelayCompat;
+#[cfg(feature = "i2c")]
+use crate::interface::I2cInterface;
+#[cfg(feature = "uart")]
+use crate::interface::SerialInterface;
+#[cfg(any(feature = "i2c", feature = "uart"))]
+use crate::ADS122x04;
+
+/// Linux I2C bus device (`/dev/i2c-*`)
+#[cfg(feature = "i2c")]
+pub type LinuxI2c = I2cCompat<I2cdev>;
+
+/// Linux serial port (`/dev/tty*`)
+#[cfg(feature = "uart")]
+pub type LinuxSerial = SerialCompat<Serial>;
+
+/// Delay provider sleeping the current thread
+pub type LinuxDelay = DelayCompat<Delay>;
+
+/// Delay provider for `reset()` and the other methods waiting on the device
+pub fn linux_delay() -> LinuxDelay {
+    DelayCompat::new(Delay)
+}
+
+#[cfg(feature = "i2c")]
+impl ADS122x04<I2cInterface<LinuxI2c>> {
+    /// Open the I2C bus at `path`, e.g. `/dev/i2c-1`, and create a driver for the ADS122C04 at `address`
+    pub fn open_i2c(path: impl AsRef<Path>, address: u8) -> Result<Self, LinuxI2CError> {
+        Ok(ADS122x04::new_i2c(address, I2cCompat::new(I2cdev::new(path)?)))
+    }
+}
+
+#[cfg(feature = "uart")]
+impl ADS122x04<SerialInterface<LinuxSerial>> {
+    /// Open the serial port at `path`, e.g. `/dev/ttyAMA0`, and create a driver for the ADS122U04.
+    /// The port keeps its current settings, the ADS122U04 detects the baud rate from the synchronization word.
+    pub fn open_serial(path: impl AsRef<Path>) -> Result<Self, linux_embedded_hal::serial_core::Error> {
+        Ok(ADS122x04::new_serial(SerialCompat::new(Serial::open(path)?)))
+    }
+}
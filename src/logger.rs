@@ -0,0 +1,280 @@
+//! Data logger running the input channels and the internal temperature sensor on independent periods
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::scan::{prepare_sensor, select_channel, select_external_input, Channel, ExternalMux};
+use crate::sensor::Sensor;
+use crate::sink::SampleSink;
+use crate::{ConversionResult, Error, ADS122x04};
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Signal logged by a channel of the data logger
+pub enum LogSource {
+    /// an input channel, converted with the conversion of the channel
+    Input(Channel),
+    /// the internal temperature sensor in °C
+    Temperature,
+    /// the sensor at this index of the sensors passed to `DataLogger::poll_sensors()`, in its engineering units
+    Sensor(usize),
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Channel of the data logger, measured every `period` ticks of the logger clock
+pub struct LogChannel {
+    /// logged signal
+    pub source: LogSource,
+    /// measurement period in ticks of the logger clock
+    pub period: u64,
+    next_due: u64,
+}
+
+impl LogChannel {
+    /// Log `source` every `period` ticks of the logger clock, starting with the first `poll()`
+    pub fn new(source: LogSource, period: u64) -> Self {
+        LogChannel {
+            source,
+            period,
+            next_due: 0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Measurement completed by the data logger
+pub struct LogRecord {
+    /// index of the logger channel
+    pub channel: usize,
+    /// time of the measurement from the logger clock
+    pub timestamp: u64,
+    /// conversion result, `None` for the internal temperature sensor
+    pub result: Option<ConversionResult>,
+    /// value in engineering units (°C for the internal temperature sensor), NaN if a sensor reading is out of
+    /// range for the sensor
+    pub value: f32,
+}
+
+/// Preparation of a measurement, before waiting for the conversion
+enum Step<'a> {
+    /// an input channel has been selected
+    Channel(&'a Channel),
+    /// a sensor has been configured and needs this settling time in µs
+    Sensor(u32),
+}
+
+/// Data logger measuring each channel on its own period, e.g. the temperature every 10 s and a bridge at 10 Hz.
+/// The device is switched to single-shot mode for the measurements and its configuration restored afterwards.
+/// Input channels are selected like in a `Scanner`, including its settling discards and external multiplexer,
+/// sensors are prepared like in a `SensorScanner`. The records can be collected in a `Recorder` with `poll_into()`.
+pub struct DataLogger<const N: usize> {
+    channels: [LogChannel; N],
+    clock: fn() -> u64,
+    settling_discards: Option<u8>,
+}
+
+impl<const N: usize> DataLogger<N> {
+    /// Create a data logger with the monotonic clock used for scheduling and timestamping
+    pub fn new(channels: [LogChannel; N], clock: fn() -> u64) -> Self {
+        DataLogger {
+            channels,
+            clock,
+            settling_discards: None,
+        }
+    }
+
+    /// Access the channels, e.g. to change their periods
+    pub fn channels_mut(&mut self) -> &mut [LogChannel; N] {
+        &mut self.channels
+    }
+
+    /// Discard at least `count` conversions after selecting an input channel, see `Scanner::set_settling_discards()`
+    pub fn set_settling_discards(&mut self, count: Option<u8>) {
+        self.settling_discards = count;
+    }
+
+    /// Measure all channels that are due and hand the records to `sink`. Returns the number of records.
+    /// A channel that fell behind by more than one period is rescheduled from now instead of catching up.
+    /// Returns `Error::InvalidValue` for a `LogSource::Sensor` channel, use `poll_sensors()` for those.
+    pub fn poll<BUS, E>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        sink: impl FnMut(&LogRecord),
+    ) -> Result<usize, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.poll_with(adc, &[], |_, _| Ok(()), sink)
+    }
+
+    /// Like `poll()`, measuring the `LogSource::Sensor` channels with `sensors`: every sensor is configured starting
+    /// from the configuration of the logger and its settling time (`Sensor::settling_us()`) is inserted before the
+    /// measurement. Input channels are measured with the reference and the excitation of the logger configuration,
+    /// also after a sensor.
+    /// Returns `Error::InvalidValue` if a channel refers to a sensor index outside of `sensors`.
+    pub fn poll_sensors<BUS, E, D>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        sensors: &[&dyn Sensor<BUS, E>],
+        delay: &mut D,
+        sink: impl FnMut(&LogRecord),
+    ) -> Result<usize, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            D: DelayNs,
+    {
+        self.poll_with(adc, sensors, |_, step| {
+            if let Step::Sensor(settling_us) = step {
+                delay.delay_us(settling_us);
+            }
+            Ok(())
+        }, sink)
+    }
+
+    /// Like `poll_sensors()`, switching the external multiplexer for input channels with an external input like
+    /// `Scanner::scan_with_external_mux()`. Returns `Error::PinError` if a select line cannot be driven.
+    pub fn poll_with_external_mux<BUS, E, P, D, const K: usize>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        sensors: &[&dyn Sensor<BUS, E>],
+        external: &mut ExternalMux<P, K>,
+        delay: &mut D,
+        sink: impl FnMut(&LogRecord),
+    ) -> Result<usize, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            P: OutputPin,
+            D: DelayNs,
+    {
+        self.poll_with(adc, sensors, |adc, step| match step {
+            Step::Channel(channel) => select_external_input(adc, channel, external, delay),
+            Step::Sensor(settling_us) => {
+                delay.delay_us(settling_us);
+                Ok(())
+            }
+        }, sink)
+    }
+
+    /// Measure all channels that are due and push the records to `sink`, e.g. a `Recorder`, see `poll()`.
+    /// Returns the number of records accepted by the sink, records rejected by the sink are dropped.
+    pub fn poll_into<BUS, E, S>(&mut self, adc: &mut ADS122x04<BUS>, sink: &mut S) -> Result<usize, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            S: SampleSink<LogRecord>,
+    {
+        let mut accepted = 0;
+        self.poll(adc, |record| accepted += Self::push(sink, record))?;
+        Ok(accepted)
+    }
+
+    /// Like `poll_into()`, measuring the `LogSource::Sensor` channels with `sensors`, see `poll_sensors()`
+    pub fn poll_sensors_into<BUS, E, D, S>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        sensors: &[&dyn Sensor<BUS, E>],
+        delay: &mut D,
+        sink: &mut S,
+    ) -> Result<usize, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            D: DelayNs,
+            S: SampleSink<LogRecord>,
+    {
+        let mut accepted = 0;
+        self.poll_sensors(adc, sensors, delay, |record| accepted += Self::push(sink, record))?;
+        Ok(accepted)
+    }
+
+    /// push a record to the sink, returns 1 if it was accepted
+    fn push<S: SampleSink<LogRecord>>(sink: &mut S, record: &LogRecord) -> usize {
+        usize::from(sink.push(record).is_ok())
+    }
+
+    fn poll_with<BUS, E>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        sensors: &[&dyn Sensor<BUS, E>],
+        mut prepare: impl FnMut(&mut ADS122x04<BUS>, Step<'_>) -> Result<(), Error<E>>,
+        mut sink: impl FnMut(&LogRecord),
+    ) -> Result<usize, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let now = (self.clock)();
+        if !self.channels.iter().any(|channel| channel.next_due <= now) {
+            return Ok(0);
+        }
+        let mut config = adc.config();
+        config.conversion_mode(ConversionMode::SingleShot);
+        let clock = self.clock;
+        let settling_discards = self.settling_discards;
+        let channels = &mut self.channels;
+        adc.with_temporary_config(config, |adc| {
+            let mut count = 0;
+            for (index, channel) in channels.iter_mut().enumerate() {
+                if channel.next_due > now {
+                    continue;
+                }
+                let record = match &channel.source {
+                    LogSource::Input(input) => {
+                        // restore the reference and the excitation changed by a sensor measured before
+                        let mut input_config = config;
+                        input_config.mux(input.mux).gain(input.gain);
+                        adc.apply_config(input_config)?;
+                        select_channel(adc, input, settling_discards)?;
+                        prepare(adc, Step::Channel(input))?;
+                        let (timestamp, result) = Self::convert(adc, clock)?;
+                        let value = input.convert(adc.convert_raw_to_voltage(result.raw));
+                        LogRecord {
+                            channel: index,
+                            timestamp,
+                            result: Some(result),
+                            value,
+                        }
+                    }
+                    LogSource::Sensor(sensor) => {
+                        let sensor = sensors.get(*sensor).ok_or(Error::InvalidValue)?;
+                        prepare_sensor(adc, config, *sensor, |_| {})?;
+                        prepare(adc, Step::Sensor(sensor.settling_us()))?;
+                        let (timestamp, result) = Self::convert(adc, clock)?;
+                        let value = sensor.convert_raw(adc, result.raw)?.unwrap_or(f32::NAN);
+                        LogRecord {
+                            channel: index,
+                            timestamp,
+                            result: Some(result),
+                            value,
+                        }
+                    }
+                    LogSource::Temperature => LogRecord {
+                        channel: index,
+                        timestamp: clock(),
+                        result: None,
+                        value: adc.measure_temperature()?,
+                    },
+                };
+                sink(&record);
+                count += 1;
+                channel.next_due = if now - channel.next_due >= channel.period {
+                    now + channel.period
+                } else {
+                    channel.next_due + channel.period
+                };
+            }
+            Ok(count)
+        })
+    }
+
+    /// timestamp and read a conversion
+    fn convert<BUS, E>(adc: &mut ADS122x04<BUS>, clock: fn() -> u64) -> Result<(u64, ConversionResult), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let timestamp = clock();
+        adc.wait_for_conversion()?;
+        Ok((timestamp, adc.read_conversion()?))
+    }
+}
@@ -0,0 +1,47 @@
+//! Duty-cycled sampling for battery powered applications
+
+use embedded_hal::delay::DelayNs;
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{Error, ADS122x04};
+
+/// Takes single-shot samples and keeps the device in power-down mode in between
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LowPowerSampler {
+    /// time in us the reference and excitation current sources need to settle after waking up
+    pub settling_us: u32,
+}
+
+impl LowPowerSampler {
+    /// Create a sampler waiting `settling_us` after waking up the device
+    pub fn new(settling_us: u32) -> Self {
+        LowPowerSampler { settling_us }
+    }
+
+    /// Take one sample: wake the device, wait for the settling time, trigger a single-shot conversion,
+    /// sleep for its conversion latency, read it and put the device back into power-down mode.
+    /// The device is powered down even if the conversion fails.
+    pub fn sample<BUS, E, D>(&self, adc: &mut ADS122x04<BUS>, delay: &mut D) -> Result<i32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            D: DelayNs,
+    {
+        let reading = self.convert(adc, delay);
+        adc.power_down()?;
+        reading
+    }
+
+    fn convert<BUS, E, D>(&self, adc: &mut ADS122x04<BUS>, delay: &mut D) -> Result<i32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            D: DelayNs,
+    {
+        adc.set_conversion_mode(ConversionMode::SingleShot)?;
+        // START/SYNC wakes the device up, the conversion started with it is discarded
+        adc.start()?;
+        delay.delay_us(self.settling_us);
+        adc.measure_with_delay(delay)
+    }
+}
@@ -0,0 +1,317 @@
+//! Simulated device for testing measurement logic without hardware
+//!
+//! `MockAds122x04` models the register file, the conversion timing (DRDY) and the conversion results,
+//! so an `ADS122x04` driver created with `ADS122x04::new_mock()` behaves like one attached to a real device.
+//! Faults can be scheduled with `inject_fault()` to exercise error handling and retry paths deterministically.
+
+use crate::codec::*;
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{private, Error, ADS122x04};
+
+/// number of configuration registers
+const NUM_REGISTERS: usize = 5;
+/// maximum number of scheduled faults
+const MAX_FAULTS: usize = 8;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Communication error of the simulated bus
+pub enum MockError {
+    /// The device did not acknowledge the transaction
+    Nack,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Fault injected into a transaction of the simulated device
+pub enum Fault {
+    /// The transaction is not acknowledged and fails with `MockError::Nack`
+    Nack,
+    /// A UART byte is dropped: reads time out with `Error::Timeout`, writes are not seen by the device
+    DroppedByte,
+    /// The data read back is corrupted in transfer, so it does not match its CRC
+    CorruptedCrc,
+    /// DRDY is stuck low from this transaction on, until the faults are cleared
+    StuckDataReady,
+}
+
+/// Simulated ADS122x04 implementing the bus traits of the driver
+#[derive(Debug, Clone)]
+pub struct MockAds122x04 {
+    registers: [u8; NUM_REGISTERS],
+    values: [i32; 16],
+    temperature: i32,
+    generator: Option<fn(&DeviceConfig, u32) -> i32>,
+    conversion_polls: u32,
+    pending: Option<u32>,
+    data_ready: bool,
+    data: u32,
+    conversions: u32,
+    register_writes: u32,
+    script: [Option<(u32, Fault)>; MAX_FAULTS],
+    stuck_data_ready: bool,
+}
+
+impl Default for MockAds122x04 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockAds122x04 {
+    /// Create a simulated device in its reset state, all inputs read zero
+    pub fn new() -> Self {
+        MockAds122x04 {
+            registers: [0; NUM_REGISTERS],
+            values: [0; 16],
+            temperature: 0,
+            generator: None,
+            conversion_polls: 0,
+            pending: None,
+            data_ready: false,
+            data: 0,
+            conversions: 0,
+            register_writes: 0,
+            script: [None; MAX_FAULTS],
+            stuck_data_ready: false,
+        }
+    }
+
+    /// Schedule a fault for the transaction `transactions` transactions from now (0 for the next one).
+    /// Returns `Error::InvalidValue` if too many faults are scheduled.
+    pub fn inject_fault(&mut self, fault: Fault, transactions: u32) -> Result<(), Error<MockError>> {
+        let slot = self.script.iter_mut().find(|slot| slot.is_none()).ok_or(Error::InvalidValue)?;
+        *slot = Some((transactions, fault));
+        Ok(())
+    }
+
+    /// Remove all scheduled faults and release a stuck DRDY
+    pub fn clear_faults(&mut self) {
+        self.script = [None; MAX_FAULTS];
+        self.stuck_data_ready = false;
+    }
+
+    /// advance the fault script by one transaction and return the fault hitting it
+    fn next_fault(&mut self) -> Option<Fault> {
+        let mut hit = None;
+        for slot in self.script.iter_mut() {
+            match slot {
+                Some((0, fault)) if hit.is_none() => {
+                    hit = Some(*fault);
+                    *slot = None;
+                }
+                // a second fault due at the same time hits the following transaction
+                Some((0, _)) => {}
+                Some((n, _)) => *n -= 1,
+                None => {}
+            }
+        }
+        match hit {
+            Some(Fault::StuckDataReady) => {
+                self.stuck_data_ready = true;
+                None
+            }
+            fault => fault,
+        }
+    }
+
+    /// Set the raw conversion result of an input multiplexer setting
+    pub fn set_conversion(&mut self, mux: Mux, raw: i32) {
+        self.values[mux as usize] = raw;
+    }
+
+    /// Set the raw conversion result in temperature sensor mode (14-bit temperature left-justified in 24 bits)
+    pub fn set_temperature_raw(&mut self, raw: i32) {
+        self.temperature = raw;
+    }
+
+    /// Generate the conversion results from the current configuration and the number of the conversion
+    /// instead of the fixed values (`None` to use the fixed values again)
+    pub fn set_generator(&mut self, generator: Option<fn(&DeviceConfig, u32) -> i32>) {
+        self.generator = generator;
+    }
+
+    /// Number of DRDY polls (reads of configuration register 2) a conversion takes to complete
+    pub fn set_conversion_polls(&mut self, polls: u32) {
+        self.conversion_polls = polls;
+    }
+
+    /// Current value of a register
+    pub fn register(&self, reg: u8) -> u8 {
+        self.registers[reg as usize]
+    }
+
+    /// Configuration currently stored in the registers.
+    /// The reference voltage is not stored on the device and reads as 0 V.
+    pub fn config(&self) -> DeviceConfig {
+        let mut config = DeviceConfig::default();
+        for reg in 0..4 {
+            config.decode(reg, self.registers[reg as usize]);
+        }
+        config
+    }
+
+    /// Number of completed conversions
+    pub fn conversions(&self) -> u32 {
+        self.conversions
+    }
+
+    /// Number of register writes received, e.g. to check that unchanged registers are not written
+    pub fn register_writes(&self) -> u32 {
+        self.register_writes
+    }
+
+    /// a conversion is running continuously
+    fn continuous(&self) -> bool {
+        matches!(decode_reg1(self.registers[1]).conversion_mode, ConversionMode::Continuous)
+    }
+
+    /// transmit the conversion data frame with the given data counter and data integrity settings
+    fn transmit_frame(&mut self, frame: &mut [u8], counter: bool, crc: Crc) -> Result<(), Error<MockError>> {
+        let fault = self.next_fault();
+        match fault {
+            Some(Fault::Nack) => return Err(Error::CommError(MockError::Nack)),
+            Some(Fault::DroppedByte) => return Err(Error::Timeout),
+            _ => {}
+        }
+        let data_frame = DataFrame {
+            counter: counter.then_some(self.conversions as u8),
+            data: self.data,
+        };
+        let mut buffer = [0; MAX_DATA_FRAME_LEN];
+        let len = encode_data_frame(&data_frame, crc, &mut buffer);
+        if frame.len() != len {
+            return Err(Error::InvalidValue);
+        }
+        if fault == Some(Fault::CorruptedCrc) {
+            // corrupt the data, not the check bytes
+            buffer[counter as usize + 2] ^= 0x01;
+        }
+        frame.copy_from_slice(&buffer[..len]);
+        self.data_ready = false;
+        Ok(())
+    }
+
+    /// advance the running conversion by one DRDY poll
+    fn poll(&mut self) {
+        match self.pending {
+            Some(0) => self.complete(),
+            Some(n) => self.pending = Some(n - 1),
+            None => {}
+        }
+    }
+
+    /// latch the result of the running conversion
+    fn complete(&mut self) {
+        let config = self.config();
+        let raw = match self.generator {
+            Some(generator) => generator(&config, self.conversions),
+            None if config.temperature_sensor_mode => self.temperature,
+            None => self.values[config.mux as usize],
+        };
+        self.data = (raw.clamp(-0x800000, 0x7FFFFF) as u32) & 0xFFFFFF;
+        self.data_ready = true;
+        self.conversions = self.conversions.wrapping_add(1);
+        self.pending = if self.continuous() { Some(self.conversion_polls) } else { None };
+    }
+}
+
+impl private::Sealed for MockAds122x04 {}
+
+impl WriteData for MockAds122x04 {
+    type Error = Error<MockError>;
+    fn write_register(&mut self, register: u8, data: u8) -> Result<(), Self::Error> {
+        match self.next_fault() {
+            Some(Fault::Nack) => return Err(Error::CommError(MockError::Nack)),
+            Some(Fault::DroppedByte) => return Ok(()),
+            _ => {}
+        }
+        self.register_writes = self.register_writes.wrapping_add(1);
+        match self.registers.get_mut(register as usize) {
+            // DRDY is read-only
+            Some(reg) if register == 0x02 => *reg = data & 0x7F,
+            Some(reg) => *reg = data,
+            None => return Err(Error::InvalidValue),
+        }
+        Ok(())
+    }
+
+    fn write_data(&mut self, payload: u8) -> Result<(), Self::Error> {
+        match self.next_fault() {
+            Some(Fault::Nack) => return Err(Error::CommError(MockError::Nack)),
+            Some(Fault::DroppedByte) => return Ok(()),
+            _ => {}
+        }
+        match payload {
+            p if p == Commands::Reset as u8 => {
+                let mock = MockAds122x04 {
+                    registers: [0; NUM_REGISTERS],
+                    pending: None,
+                    data_ready: false,
+                    data: 0,
+                    ..self.clone()
+                };
+                *self = mock;
+            }
+            p if p == Commands::StartSync as u8 => {
+                self.data_ready = false;
+                self.pending = Some(self.conversion_polls);
+            }
+            p if p & 0xFE == Commands::PowerDown as u8 => self.pending = None,
+            _ => return Err(Error::InvalidValue),
+        }
+        Ok(())
+    }
+}
+
+impl ReadData for MockAds122x04 {
+    type Error = Error<MockError>;
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        let fault = self.next_fault();
+        match fault {
+            Some(Fault::Nack) => return Err(Error::CommError(MockError::Nack)),
+            Some(Fault::DroppedByte) => return Err(Error::Timeout),
+            _ => {}
+        }
+        let val = if register == 0x02 {
+            self.poll();
+            let data_ready = self.data_ready && !self.stuck_data_ready;
+            self.registers[2] | ((data_ready as u8) << 7)
+        } else {
+            self.registers.get(register as usize).copied().ok_or(Error::InvalidValue)?
+        };
+        match fault {
+            Some(Fault::CorruptedCrc) => Ok(val ^ 0x01),
+            _ => Ok(val),
+        }
+    }
+
+    fn read_data(&mut self) -> Result<u32, Self::Error> {
+        let mut frame = [0; 3];
+        self.transmit_frame(&mut frame, false, Crc::Disabled)?;
+        Ok((frame[0] as u32) << 16 | (frame[1] as u32) << 8 | (frame[2] as u32))
+    }
+
+    fn read_frame(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        let reg2 = decode_reg2(self.registers[2]);
+        self.transmit_frame(frame, reg2.data_counter_enable, reg2.crc)
+    }
+}
+
+impl ADS122x04<MockAds122x04> {
+    /// Create a driver attached to a simulated device
+    pub fn new_mock(mock: MockAds122x04) -> Self {
+        Self::with_bus(mock)
+    }
+
+    /// Access the simulated device, e.g. to change the conversion results
+    pub fn mock(&mut self) -> &mut MockAds122x04 {
+        &mut self.bus
+    }
+
+    /// Destroy the driver and return the simulated device
+    pub fn release(self) -> MockAds122x04 {
+        self.bus
+    }
+}
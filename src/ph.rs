@@ -0,0 +1,104 @@
+//! pH and other electrochemical probes with a high-impedance electrode
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{Error, ADS122x04};
+
+/// 0 °C in Kelvin
+const ZERO_CELSIUS: f32 = 273.15;
+/// Nernst slope at 25 °C in mV/pH
+const NERNST_SLOPE_25C: f32 = -59.16;
+/// pH of the neutral point, where an ideal electrode outputs 0 mV
+const NEUTRAL_PH: f32 = 7.0;
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// pH electrode measured differentially at gain 1 with the PGA bypassed, at the lowest data rate and averaged over
+/// many conversions.
+/// The electrode output is bipolar (about ±400 mV around pH 7), so the reference electrode must not be tied to
+/// AVSS: bias it above AVSS, e.g. to mid-supply, or connect it to an AIN pin wired to REFN of a biased reference,
+/// so the measuring electrode stays within the input range for both polarities.
+/// The PGA is bypassed because it adds no gain at gain 1, and without it the inputs may swing from AVSS - 0.1 V to
+/// AVDD + 0.1 V instead of being limited by the PGA headroom, which leaves the bias point free.
+/// The unbuffered switched-capacitor input draws more input current than the PGA, which loads the high-impedance
+/// electrode and shifts the reading: buffer the electrode (e.g. with a low-bias op amp) unless its source impedance
+/// is low enough for the resulting error.
+pub struct PhProbe {
+    /// differential input the electrode is connected to
+    pub mux: Mux,
+    /// electrode voltage at pH 7 in mV (calibration offset)
+    pub offset_mv: f32,
+    /// electrode slope at 25 °C in mV/pH, -59.16 mV/pH for an ideal electrode
+    pub slope_mv_per_ph: f32,
+    /// number of conversions averaged per reading
+    pub averages: u32,
+}
+
+impl PhProbe {
+    /// Create an uncalibrated probe with the ideal Nernst slope, averaging `averages` conversions per reading
+    pub fn new(mux: Mux, averages: u32) -> Self {
+        PhProbe {
+            mux,
+            offset_mv: 0.0,
+            slope_mv_per_ph: NERNST_SLOPE_25C,
+            averages,
+        }
+    }
+
+    /// Configure the input with the PGA bypassed and the lowest data rate
+    pub fn configure<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        adc.set_input_mux(self.mux)?;
+        adc.set_gain(Gain::Gain1)?;
+        adc.set_pga_bypass(true)?;
+        adc.set_data_rate(DataRate::Sps20Normal)
+    }
+
+    /// Read the averaged electrode voltage in mV
+    pub fn read_millivolts<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<f32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let reading = adc.read_averaged(self.averages)?;
+        Ok(adc.convert_raw_to_voltage(reading.mean) * 1000.0)
+    }
+
+    /// Read the pH at 25 °C
+    pub fn read<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<f32, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.read_millivolts(adc).map(|mv| self.convert(mv, 25.0))
+    }
+
+    /// Convert the electrode voltage (mV) to pH, scaling the slope to the solution temperature (°C)
+    pub fn convert(&self, millivolts: f32, temperature: f32) -> f32 {
+        let slope = self.slope_mv_per_ph * (temperature + ZERO_CELSIUS) / (25.0 + ZERO_CELSIUS);
+        NEUTRAL_PH + (millivolts - self.offset_mv) / slope
+    }
+
+    /// Calibrate the offset with the electrode in a pH 7 buffer
+    pub fn calibrate_offset<BUS, E>(&mut self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.offset_mv = self.read_millivolts(adc)?;
+        Ok(())
+    }
+
+    /// Calibrate the slope with the electrode in a second buffer of `buffer_ph` (e.g. pH 4 or pH 10) at 25 °C,
+    /// after calibrating the offset. Returns `Error::InvalidValue` for a pH 7 buffer.
+    pub fn calibrate_slope<BUS, E>(&mut self, adc: &mut ADS122x04<BUS>, buffer_ph: f32) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        if buffer_ph == NEUTRAL_PH {
+            return Err(Error::InvalidValue);
+        }
+        let millivolts = self.read_millivolts(adc)?;
+        self.slope_mv_per_ph = (millivolts - self.offset_mv) / (buffer_ph - NEUTRAL_PH);
+        Ok(())
+    }
+}
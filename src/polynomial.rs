@@ -0,0 +1,85 @@
+//! Polynomial correction for sensor linearization and board-level error correction
+//!
+//! The coefficients typically come from a calibration stored with the device, e.g. a gain and offset
+//! correction of the front-end followed by a quadratic sensor characteristic:
+//!
+//! ```ignore
+//! let correction = Polynomial::<4>::from_slice(&stored_coefficients).unwrap();
+//! let channel = Channel::new(Mux::Ain0Ain1, Gain::Gain16).with_correction(correction);
+//! ```
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Polynomial `c0 + c1 x + c2 x^2 + ...` with `N` coefficients (degree `N - 1`), evaluated with Horner's method
+pub struct Polynomial<const N: usize> {
+    coefficients: [f32; N],
+}
+
+impl<const N: usize> Polynomial<N> {
+    /// Create a polynomial from its coefficients in ascending order, `coefficients[0]` is the constant term
+    pub const fn new(coefficients: [f32; N]) -> Self {
+        Polynomial { coefficients }
+    }
+
+    /// Create a polynomial from up to `N` coefficients in ascending order, the missing higher order
+    /// coefficients are zero. Returns `None` if there are more than `N` coefficients.
+    pub fn from_slice(coefficients: &[f32]) -> Option<Self> {
+        if coefficients.len() > N {
+            return None;
+        }
+        let mut polynomial = Polynomial { coefficients: [0.0; N] };
+        polynomial.coefficients[..coefficients.len()].copy_from_slice(coefficients);
+        Some(polynomial)
+    }
+
+    /// Identity `x`, the neutral correction. Fails to compile for `N < 2`, which cannot represent `x`.
+    pub fn identity() -> Self {
+        const { assert!(N >= 2, "the identity needs at least two coefficients") };
+        let mut polynomial = Polynomial { coefficients: [0.0; N] };
+        polynomial.coefficients[1] = 1.0;
+        polynomial
+    }
+
+    /// The coefficients in ascending order
+    pub fn coefficients(&self) -> &[f32; N] {
+        &self.coefficients
+    }
+
+    /// Evaluate the polynomial at `x`
+    pub fn evaluate(&self, x: f32) -> f32 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &coefficient| acc * x + coefficient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn horner_evaluation() {
+        // 1 - 2x + 0.5x^2 + 0.25x^3 at x = 2: 1 - 4 + 2 + 2 = 1, at x = -3: 1 + 6 + 4.5 - 6.75 = 4.75
+        let polynomial = Polynomial::new([1.0, -2.0, 0.5, 0.25]);
+        assert_eq!(polynomial.evaluate(0.0), 1.0);
+        assert_eq!(polynomial.evaluate(2.0), 1.0);
+        assert_eq!(polynomial.evaluate(-3.0), 4.75);
+    }
+
+    #[test]
+    fn from_slice_pads_higher_orders() {
+        let polynomial = Polynomial::<4>::from_slice(&[0.5, 2.0]).unwrap();
+        assert_eq!(polynomial.coefficients(), &[0.5, 2.0, 0.0, 0.0]);
+        assert_eq!(polynomial.evaluate(3.0), 6.5);
+        assert!(Polynomial::<2>::from_slice(&[1.0, 2.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn identity() {
+        let identity = Polynomial::<3>::identity();
+        for x in [-1.5, 0.0, 42.0] {
+            assert_eq!(identity.evaluate(x), x);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Ring buffer recorder keeping the latest conversions for post-trigger analysis
+
+use heapless::HistoryBuffer;
+use heapless::OldestOrdered;
+
+use crate::filter::Filter;
+use crate::interface::{ReadData, WriteData};
+use crate::stream::ContinuousReader;
+use crate::{ConversionResult, Error};
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Conversion stored in the recorder
+pub struct Record {
+    /// timestamp supplied by the clock of the recorder
+    pub timestamp: u64,
+    /// the conversion result
+    pub result: ConversionResult,
+}
+
+#[cfg(feature = "postcard")]
+impl Record {
+    /// Encode the record with postcard into `buffer`, returns the used part of the buffer
+    pub fn to_postcard<'a>(&self, buffer: &'a mut [u8]) -> postcard::Result<&'a mut [u8]> {
+        postcard::to_slice(self, buffer)
+    }
+
+    /// Decode a record encoded with `to_postcard()`
+    pub fn from_postcard(bytes: &[u8]) -> postcard::Result<Self> {
+        postcard::from_bytes(bytes)
+    }
+}
+
+/// Stores the latest N conversions, overwriting the oldest one when full.
+/// Every conversion is timestamped with the user supplied monotonic clock.
+pub struct Recorder<const N: usize> {
+    buffer: HistoryBuffer<Record, N>,
+    clock: fn() -> u64,
+}
+
+impl<const N: usize> Recorder<N> {
+    /// Create an empty recorder with the clock used to timestamp the conversions
+    pub fn new(clock: fn() -> u64) -> Self {
+        Recorder {
+            buffer: HistoryBuffer::new(),
+            clock,
+        }
+    }
+
+    /// Store a conversion, overwriting the oldest one if the recorder is full
+    pub fn record(&mut self, result: ConversionResult) {
+        let timestamp = (self.clock)();
+        self.buffer.write(Record { timestamp, result });
+    }
+
+    /// Store a conversion with the given timestamp instead of one from the clock of the recorder,
+    /// e.g. a conversion timestamped by the data logger
+    pub fn record_at(&mut self, timestamp: u64, result: ConversionResult) {
+        self.buffer.write(Record { timestamp, result });
+    }
+
+    /// Read the next conversion from the continuous reader and store it
+    pub fn record_next<BUS, E, F>(&mut self, reader: &mut ContinuousReader<'_, BUS, F>) -> Result<ConversionResult, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            F: Filter,
+    {
+        let result = reader.next_conversion()?;
+        self.record(result);
+        Ok(result)
+    }
+
+    /// The most recent conversion
+    pub fn latest(&self) -> Option<&Record> {
+        self.buffer.recent()
+    }
+
+    /// Iterate over the stored conversions from the oldest to the newest
+    pub fn iter(&self) -> OldestOrdered<'_, Record, N> {
+        self.buffer.oldest_ordered()
+    }
+
+    /// Number of stored conversions
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// No conversion has been stored yet
+    pub fn is_empty(&self) -> bool {
+        self.buffer.len() == 0
+    }
+
+    /// Discard all stored conversions
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
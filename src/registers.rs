@@ -1,5 +1,6 @@
 //! ADS122x04 registers and commands
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code)]
 /// Commands to send to the device
 pub enum Commands {
@@ -17,7 +18,8 @@ pub enum Commands {
     WReg = 0b1000000,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code, missing_docs)]
 pub enum Mux {
     Ain0Ain1 = 0b0000,
@@ -37,7 +39,91 @@ pub enum Mux {
     Shorted = 0b1110,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[allow(dead_code, missing_docs)]
+impl Mux {
+    pub fn from(val: u8) -> Self {
+        match val {
+            0b0000 => Self::Ain0Ain1,
+            0b0001 => Self::Ain0Ain2,
+            0b0010 => Self::Ain0Ain3,
+            0b0011 => Self::Ain1Ain0,
+            0b0100 => Self::Ain1Ain2,
+            0b0101 => Self::Ain1Ain3,
+            0b0110 => Self::Ain2Ain3,
+            0b0111 => Self::Ain3Ain2,
+            0b1000 => Self::Ain0Avss,
+            0b1001 => Self::Ain1Avss,
+            0b1010 => Self::Ain2Avss,
+            0b1011 => Self::Ain3Avss,
+            0b1100 => Self::VrefMonitor,
+            0b1101 => Self::AvddMonitor,
+            _ => Self::Shorted,
+        }
+    }
+
+    /// Positive and negative input of the multiplexer setting, `None` for the monitors and the shorted inputs
+    pub const fn inputs(&self) -> Option<(AnalogInput, AnalogInput)> {
+        use AnalogInput::*;
+        match self {
+            Mux::Ain0Ain1 => Some((Ain0, Ain1)),
+            Mux::Ain0Ain2 => Some((Ain0, Ain2)),
+            Mux::Ain0Ain3 => Some((Ain0, Ain3)),
+            Mux::Ain1Ain0 => Some((Ain1, Ain0)),
+            Mux::Ain1Ain2 => Some((Ain1, Ain2)),
+            Mux::Ain1Ain3 => Some((Ain1, Ain3)),
+            Mux::Ain2Ain3 => Some((Ain2, Ain3)),
+            Mux::Ain3Ain2 => Some((Ain3, Ain2)),
+            Mux::Ain0Avss => Some((Ain0, Avss)),
+            Mux::Ain1Avss => Some((Ain1, Avss)),
+            Mux::Ain2Avss => Some((Ain2, Avss)),
+            Mux::Ain3Avss => Some((Ain3, Avss)),
+            Mux::VrefMonitor | Mux::AvddMonitor | Mux::Shorted => None,
+        }
+    }
+
+    /// Multiplexer setting measuring `positive` against `negative`, `None` if the device does not offer the pair.
+    /// Single-ended inputs are measured against `AnalogInput::Avss`.
+    pub const fn differential(positive: AnalogInput, negative: AnalogInput) -> Option<Mux> {
+        use AnalogInput::*;
+        match (positive, negative) {
+            (Ain0, Ain1) => Some(Mux::Ain0Ain1),
+            (Ain0, Ain2) => Some(Mux::Ain0Ain2),
+            (Ain0, Ain3) => Some(Mux::Ain0Ain3),
+            (Ain1, Ain0) => Some(Mux::Ain1Ain0),
+            (Ain1, Ain2) => Some(Mux::Ain1Ain2),
+            (Ain1, Ain3) => Some(Mux::Ain1Ain3),
+            (Ain2, Ain3) => Some(Mux::Ain2Ain3),
+            (Ain3, Ain2) => Some(Mux::Ain3Ain2),
+            (Ain0, Avss) => Some(Mux::Ain0Avss),
+            (Ain1, Avss) => Some(Mux::Ain1Avss),
+            (Ain2, Avss) => Some(Mux::Ain2Avss),
+            (Ain3, Avss) => Some(Mux::Ain3Avss),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Analog input pin selectable by the input multiplexer
+pub enum AnalogInput {
+    /// AIN0
+    Ain0,
+    /// AIN1
+    Ain1,
+    /// AIN2
+    Ain2,
+    /// AIN3
+    Ain3,
+    /// analog ground, the negative input of single-ended measurements
+    Avss,
+}
+
+/// tolerance of the internal oscillator added to the conversion latency
+const OSCILLATOR_TOLERANCE_PERCENT: u32 = 2;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code, missing_docs)]
 pub enum DataRate {
     Sps20Normal = 0b0000,
@@ -79,7 +165,104 @@ impl DataRate {
     }
 }
 
+impl DataRate {
+    /// Output data rate in samples per second
+    pub const fn samples_per_second(&self) -> u16 {
+        match self {
+            DataRate::Sps20Normal => 20,
+            DataRate::Sps45Normal => 45,
+            DataRate::Sps90Normal => 90,
+            DataRate::Sps175Normal => 175,
+            DataRate::Sps330Normal => 330,
+            DataRate::Sps600Normal => 600,
+            DataRate::Sps1000Normal => 1000,
+            DataRate::Sps40Turbo => 40,
+            DataRate::Sps90Turbo => 90,
+            DataRate::Sps180Turbo => 180,
+            DataRate::Sps350Turbo => 350,
+            DataRate::Sps660Turbo => 660,
+            DataRate::Sps1200Turbo => 1200,
+            DataRate::Sps2000Turbo => 2000,
+        }
+    }
+
+    /// Time (us) from START/SYNC to data ready of a single-shot conversion, also when waking up from power-down,
+    /// after the conversion time table of the datasheet with a margin for the tolerance of the internal oscillator
+    pub const fn single_shot_latency_us(&self) -> u32 {
+        // conversion time in modulator clock periods of the DR setting, the same in normal and turbo mode
+        let periods: u32 = match *self as u8 >> 1 {
+            0b000 => 12854,
+            0b001 => 5718,
+            0b010 => 2874,
+            0b011 => 1479,
+            0b100 => 798,
+            0b101 => 454,
+            _ => 290,
+        };
+        // 256 kHz modulator clock in normal mode, 512 kHz in turbo mode
+        let clock_khz = match *self as u8 & 0b1 {
+            0 => 256,
+            _ => 512,
+        };
+        (periods * 1000 / clock_khz) * (100 + OSCILLATOR_TOLERANCE_PERCENT) / 100 + 1
+    }
+
+    /// Operating mode (MODE) contained in the lowest bit of the data rate
+    pub fn operating_mode(&self) -> OperatingMode {
+        OperatingMode::from(*self as u8 & 0b1)
+    }
+
+    /// Data rate with the same DR setting in the given operating mode, e.g. 20 SPS normal becomes 40 SPS turbo
+    pub fn with_operating_mode(&self, mode: OperatingMode) -> Self {
+        Self::from((*self as u8 & !0b1) | mode as u8)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Operating mode (MODE): turbo mode doubles the modulator clock and thereby the data rate
+pub enum OperatingMode {
+    /// normal mode, 256 kHz modulator clock
+    Normal = 0,
+    /// turbo mode, 512 kHz modulator clock
+    Turbo = 1,
+}
+
+impl OperatingMode {
+    /// Decode the MODE bit
+    pub fn from(val: u8) -> Self {
+        match val {
+            0 => Self::Normal,
+            _ => Self::Turbo,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Mains frequency to be rejected by the digital filter
+pub enum LineFrequency {
+    /// 50 Hz mains
+    Hz50,
+    /// 60 Hz mains
+    Hz60,
+    /// simultaneous 50 Hz and 60 Hz rejection
+    Both,
+}
+
+impl LineFrequency {
+    /// Data rate whose digital filter notches the mains frequency.
+    /// The ADS122x04 offers 50 Hz and 60 Hz rejection only at 20 SPS in normal mode, where both are rejected
+    /// simultaneously, so all line frequencies map to the same data rate.
+    pub fn data_rate(&self) -> DataRate {
+        match self {
+            LineFrequency::Hz50 | LineFrequency::Hz60 | LineFrequency::Both => DataRate::Sps20Normal,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code, missing_docs)]
 pub enum Gain {
     Gain1 = 0b000,
@@ -107,9 +290,15 @@ impl Gain {
             _ => Self::Gain1,
         }
     }
+
+    /// Gain factor (1 to 128)
+    pub const fn factor(&self) -> u8 {
+        1 << (*self as u8)
+    }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code, missing_docs)]
 pub enum CurrentSource {
     Off = 0b000,
@@ -149,9 +338,24 @@ impl CurrentSource {
             CurrentSource::I1500uA => { 0.0015 }
         }
     }
+
+    /// Excitation current in uA
+    pub const fn microamps(&self) -> u16 {
+        match self {
+            CurrentSource::Off => 0,
+            CurrentSource::I10uA => 10,
+            CurrentSource::I50uA => 50,
+            CurrentSource::I100uA => 100,
+            CurrentSource::I250uA => 250,
+            CurrentSource::I500uA => 500,
+            CurrentSource::I1000uA => 1000,
+            CurrentSource::I1500uA => 1500,
+        }
+    }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code, missing_docs)]
 pub enum CurrentRoute {
     Off = 0b000,
@@ -179,6 +383,39 @@ impl CurrentRoute {
 }
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Excitation current source (IDAC)
+pub enum Idac {
+    /// excitation current source 1
+    Idac1,
+    /// excitation current source 2
+    Idac2,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// General purpose IO pin of the ADS122U04
+pub enum Gpio {
+    /// GPIO0
+    Gpio0 = 0,
+    /// GPIO1
+    Gpio1 = 1,
+    /// GPIO2
+    Gpio2 = 2,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Direction of a general purpose IO pin
+pub enum GpioDirection {
+    /// the pin drives the level written with `set_gpio_output()`
+    Output = 0,
+    /// the pin level is read with `read_gpio_input()`
+    Input = 1,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code, missing_docs)]
 pub enum ConversionMode {
     SingleShot = 0,
@@ -195,7 +432,8 @@ impl ConversionMode {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code, missing_docs)]
 pub enum Crc {
     Disabled = 0b00,
@@ -214,7 +452,11 @@ impl Crc {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// voltage of the internal reference in uV
+pub const INTERNAL_REFERENCE_MICROVOLTS: u32 = 2_048_000;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code)]
 /// Voltage reference
 pub enum VRef {
@@ -228,7 +470,7 @@ pub enum VRef {
 
 #[allow(dead_code, missing_docs)]
 impl VRef {
-    pub fn to_val(&self) -> u8 {
+    pub const fn to_val(&self) -> u8 {
         match self {
             VRef::Internal => 0b00,
             VRef::External(_) => 0b01,
@@ -244,6 +486,25 @@ impl VRef {
         }
     }
 
+    /// reference voltage in mV, rounded down
+    pub fn millivolts(&self) -> u16 {
+        match self {
+            VRef::Internal => 2048,
+            VRef::External(v) => (*v * 1000.0) as u16,
+            VRef::AnalogSupply(v) => (*v * 1000.0) as u16,
+        }
+    }
+
+    /// reference voltage in uV, rounded to the nearest uV. The internal reference needs no floating-point math,
+    /// use `ADS122x04::set_vref_microvolts()` to avoid it for an external or supply reference.
+    pub fn to_microvolts(&self) -> i64 {
+        match self {
+            VRef::Internal => INTERNAL_REFERENCE_MICROVOLTS as i64,
+            VRef::External(v) => (*v * 1_000_000.0 + 0.5) as i64,
+            VRef::AnalogSupply(v) => (*v * 1_000_000.0 + 0.5) as i64,
+        }
+    }
+
     pub fn from(val: u8, voltage: f32) -> Self {
         match val {
             0b00 => VRef::Internal,
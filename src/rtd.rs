@@ -0,0 +1,116 @@
+//! Platinum RTD (Pt100, Pt1000) measured ratiometrically against a reference resistor
+
+use libm::sqrtf;
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{Error, ADS122x04};
+
+/// Callendar–Van Dusen coefficient A of IEC 60751
+const CVD_A: f32 = 3.9083e-3;
+/// Callendar–Van Dusen coefficient B of IEC 60751
+const CVD_B: f32 = -5.775e-7;
+/// Callendar–Van Dusen coefficient C of IEC 60751, only below 0 °C
+const CVD_C: f32 = -4.183e-12;
+/// lower limit of the IEC 60751 characteristic in °C
+const MIN_TEMPERATURE: f32 = -200.0;
+/// upper limit of the IEC 60751 characteristic in °C
+const MAX_TEMPERATURE: f32 = 850.0;
+/// Newton iterations refining the temperature below 0 °C
+const NEWTON_ITERATIONS: usize = 3;
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Platinum RTD excited by an IDAC, with the reference resistor in series with the RTD between REFP and REFN.
+/// The same current flows through the RTD and the reference resistor, so the reading is the resistance ratio
+/// and the accuracy of the current source cancels out.
+pub struct Rtd {
+    /// differential input across the RTD
+    pub mux: Mux,
+    /// PGA gain
+    pub gain: Gain,
+    /// resistance at 0 °C in Ohm (100 for a Pt100)
+    pub r0: f32,
+    /// reference resistor between REFP and REFN in Ohm
+    pub reference_resistor: f32,
+    /// excitation current
+    pub current: CurrentSource,
+    /// pin the excitation current is routed to
+    pub current_route: CurrentRoute,
+}
+
+impl Rtd {
+    /// Create an RTD with the resistance `r0` (Ohm) at 0 °C, excited with `current` on `current_route`
+    pub fn new(
+        mux: Mux,
+        gain: Gain,
+        r0: f32,
+        reference_resistor: f32,
+        current: CurrentSource,
+        current_route: CurrentRoute,
+    ) -> Self {
+        Rtd {
+            mux,
+            gain,
+            r0,
+            reference_resistor,
+            current,
+            current_route,
+        }
+    }
+
+    /// Configure the MUX, gain, excitation current and the ratiometric reference of the ADC
+    pub fn configure<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        adc.set_input_mux(self.mux)?;
+        adc.set_pga_bypass(false)?;
+        adc.set_gain(self.gain)?;
+        adc.set_current_level(self.current)?;
+        adc.set_current_route_1(self.current_route)?;
+        let reference_voltage = self.current.microamps() as f32 * 1e-6 * self.reference_resistor;
+        adc.set_vref(VRef::External(reference_voltage))
+    }
+
+    /// Take a measurement and return the temperature in °C, `None` if out of range (e.g. open or shorted RTD)
+    pub fn read_temperature<BUS, E>(&self, adc: &mut ADS122x04<BUS>) -> Result<Option<f32>, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let raw = adc.measure()?;
+        Ok(self.temperature(self.resistance(adc.convert_raw_to_ratio(raw))))
+    }
+
+    /// RTD resistance (Ohm) from the ratio of the RTD voltage to the reference voltage
+    pub fn resistance(&self, ratio: f32) -> f32 {
+        ratio * self.reference_resistor
+    }
+
+    /// Temperature (°C) of the RTD with the given resistance (Ohm) following the IEC 60751 characteristic,
+    /// `None` outside of -200 °C to 850 °C
+    pub fn temperature(&self, resistance: f32) -> Option<f32> {
+        let ratio = resistance / self.r0;
+        let discriminant = CVD_A * CVD_A - 4.0 * CVD_B * (1.0 - ratio);
+        if discriminant < 0.0 {
+            return None;
+        }
+        let mut celsius = (-CVD_A + sqrtf(discriminant)) / (2.0 * CVD_B);
+        if celsius < 0.0 {
+            for _ in 0..NEWTON_ITERATIONS {
+                let t = celsius;
+                let error = 1.0 + CVD_A * t + CVD_B * t * t + CVD_C * (t - 100.0) * t * t * t - ratio;
+                let slope = CVD_A + 2.0 * CVD_B * t + CVD_C * (4.0 * t - 300.0) * t * t;
+                celsius = t - error / slope;
+            }
+        }
+        (MIN_TEMPERATURE..=MAX_TEMPERATURE).contains(&celsius).then_some(celsius)
+    }
+
+    /// Resistance (Ohm) of the RTD at the given temperature (°C) following the IEC 60751 characteristic
+    pub fn resistance_at(&self, celsius: f32) -> f32 {
+        let t = celsius;
+        let c = if t < 0.0 { CVD_C * (t - 100.0) * t * t * t } else { 0.0 };
+        self.r0 * (1.0 + CVD_A * t + CVD_B * t * t + c)
+    }
+}
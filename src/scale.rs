@@ -0,0 +1,89 @@
+//! Linear scaling of transmitter-style sensor signals to engineering units
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Position of the input signal relative to the calibrated range
+pub enum ScaleStatus {
+    /// the input is within the zero to span range
+    InRange,
+    /// the input is below the zero point
+    UnderRange,
+    /// the input is above the span point
+    OverRange,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Input signal scaled to engineering units
+pub struct ScaledValue {
+    /// value in engineering units, clamped to the output range if clamping is enabled
+    pub value: f32,
+    /// position of the input relative to the calibrated range
+    pub status: ScaleStatus,
+}
+
+impl ScaledValue {
+    /// The value if the input is within the calibrated range, `None` otherwise
+    pub fn in_range(&self) -> Option<f32> {
+        (self.status == ScaleStatus::InRange).then_some(self.value)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Linear scaling profile mapping an input signal (e.g. V, mA or mV/V) between a zero and a span point to
+/// engineering units (e.g. bar or kg)
+pub struct LinearScale {
+    /// input signal at the zero point
+    pub input_zero: f32,
+    /// input signal at the span point
+    pub input_span: f32,
+    /// engineering value at the zero point
+    pub output_zero: f32,
+    /// engineering value at the span point
+    pub output_span: f32,
+    /// clamp the output to the range between the zero and the span point
+    pub clamp: bool,
+}
+
+impl LinearScale {
+    /// Create a scaling profile mapping `input_zero..input_span` to `output_zero..output_span`, without clamping.
+    /// Returns `None` if the zero and the span point have the same input signal.
+    pub fn new(input_zero: f32, input_span: f32, output_zero: f32, output_span: f32) -> Option<Self> {
+        if input_span == input_zero {
+            return None;
+        }
+        Some(LinearScale {
+            input_zero,
+            input_span,
+            output_zero,
+            output_span,
+            clamp: false,
+        })
+    }
+
+    /// Clamp the output to the range between the zero and the span point
+    pub fn with_clamping(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// Scale the input signal to engineering units. The zero and span inputs must differ, see `new()`.
+    pub fn apply(&self, input: f32) -> ScaledValue {
+        let mut fraction = (input - self.input_zero) / (self.input_span - self.input_zero);
+        let status = if fraction < 0.0 {
+            ScaleStatus::UnderRange
+        } else if fraction > 1.0 {
+            ScaleStatus::OverRange
+        } else {
+            ScaleStatus::InRange
+        };
+        if self.clamp {
+            fraction = fraction.clamp(0.0, 1.0);
+        }
+        ScaledValue {
+            value: self.output_zero + fraction * (self.output_span - self.output_zero),
+            status,
+        }
+    }
+}
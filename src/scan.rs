@@ -0,0 +1,357 @@
+//! Scan sequencer over multiple input channels
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+use crate::interface::{ReadData, WriteData};
+use crate::polynomial::Polynomial;
+use crate::registers::*;
+use crate::codec::DeviceConfig;
+use crate::sensor::Sensor;
+use crate::{Error, ADS122x04};
+
+/// number of coefficients of the per-channel correction polynomial (up to third degree)
+pub const CORRECTION_TERMS: usize = 4;
+
+#[derive(Debug, Copy, Clone)]
+/// Channel of the scan sequence
+pub struct Channel {
+    /// input multiplexer setting
+    pub mux: Mux,
+    /// PGA gain
+    pub gain: Gain,
+    /// correction of the input voltage (V) applied before the conversion, e.g. from a board-level calibration
+    pub correction: Option<Polynomial<CORRECTION_TERMS>>,
+    /// conversion of the input voltage (V) to engineering units, e.g. °C, kg or bar
+    pub conversion: Option<fn(f32) -> f32>,
+    /// input of the external multiplexer in front of `mux`, if any
+    pub external_input: Option<u8>,
+}
+
+impl Channel {
+    /// Create a channel reporting the input voltage
+    pub fn new(mux: Mux, gain: Gain) -> Self {
+        Channel {
+            mux,
+            gain,
+            correction: None,
+            conversion: None,
+            external_input: None,
+        }
+    }
+
+    /// Measure `input` of the external multiplexer, see `Scanner::scan_with_external_mux()`
+    pub fn with_external_input(mut self, input: u8) -> Self {
+        self.external_input = Some(input);
+        self
+    }
+
+    /// Convert the input voltage (V) of this channel to engineering units with `conversion`
+    pub fn with_conversion(mut self, conversion: fn(f32) -> f32) -> Self {
+        self.conversion = Some(conversion);
+        self
+    }
+
+    /// Correct the input voltage (V) of this channel with `correction` before the conversion, e.g. for a gain and
+    /// offset calibration of the front-end or a polynomial sensor characteristic without a conversion
+    pub fn with_correction(mut self, correction: Polynomial<CORRECTION_TERMS>) -> Self {
+        self.correction = Some(correction);
+        self
+    }
+
+    /// apply the correction and the conversion to the input voltage
+    pub(crate) fn convert(&self, volts: f32) -> f32 {
+        let volts = self.correction.map_or(volts, |correction| correction.evaluate(volts));
+        self.conversion.map_or(volts, |conversion| conversion(volts))
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Channel {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Channel {{ mux: {}, gain: {}, correction: {}, conversion: {}, external_input: {} }}",
+            self.mux,
+            self.gain,
+            self.correction,
+            self.conversion.is_some(),
+            self.external_input
+        )
+    }
+}
+
+/// External analog multiplexer (e.g. CD4051, ADG708) in front of the ADC inputs, driven by `K` select lines.
+/// Select line `i` is driven with bit `i` of the input number.
+pub struct ExternalMux<P, const K: usize> {
+    pins: [P; K],
+    /// time in us the multiplexer output needs to settle after switching
+    pub settling_us: u32,
+    selected: Option<u8>,
+}
+
+impl<P: OutputPin, const K: usize> ExternalMux<P, K> {
+    /// Create an external multiplexer driven by the select lines `pins` (least significant bit first)
+    pub fn new(pins: [P; K], settling_us: u32) -> Self {
+        ExternalMux {
+            pins,
+            settling_us,
+            selected: None,
+        }
+    }
+
+    /// Destroy the multiplexer and return the select lines
+    pub fn release(self) -> [P; K] {
+        self.pins
+    }
+
+    /// Drive the select lines for `input`, returns `true` if the multiplexer has been switched
+    pub fn select(&mut self, input: u8) -> Result<bool, P::Error> {
+        if self.selected == Some(input) {
+            return Ok(false);
+        }
+        // invalidate the selection in case driving a pin fails halfway
+        self.selected = None;
+        for (bit, pin) in self.pins.iter_mut().enumerate() {
+            if input & (1 << bit) != 0 {
+                pin.set_high()?;
+            } else {
+                pin.set_low()?;
+            }
+        }
+        self.selected = Some(input);
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Result of a scan cycle
+pub struct ScanResult<const N: usize> {
+    /// raw ADC values of the channels with the offset subtracted
+    pub values: [i32; N],
+    /// values corrected and converted with the correction and the conversion of the channel, the input voltage (V)
+    /// for channels without either
+    pub converted: [f32; N],
+    /// internal temperature in °C, if it was measured in this cycle
+    pub temperature: Option<f32>,
+    /// timestamp at the start of the cycle from the clock set with `ADS122x04::set_clock()`, if any
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Scan sequencer measuring a fixed set of channels one after another
+pub struct Scanner<const N: usize> {
+    channels: [Channel; N],
+    temperature_interval: Option<u32>,
+    cycle: u32,
+    settling_discards: Option<u8>,
+}
+
+impl<const N: usize> Scanner<N> {
+    /// Create a new scanner for the given channels
+    pub fn new(channels: [Channel; N]) -> Self {
+        Scanner {
+            channels,
+            temperature_interval: None,
+            cycle: 0,
+            settling_discards: None,
+        }
+    }
+
+    /// Discard at least `count` conversions after every channel switch, in either conversion mode, e.g. for external
+    /// input filters. The settling tracking of the device (see `ADS122x04::set_settling_discards()`) applies in
+    /// addition, with `None` (the default) it applies alone.
+    pub fn set_settling_discards(&mut self, count: Option<u8>) {
+        self.settling_discards = count;
+    }
+
+    /// Insert an internal temperature sensor reading every `cycles` scan cycles (`None` to disable)
+    pub fn set_temperature_interval(&mut self, cycles: Option<u32>) {
+        self.temperature_interval = cycles.filter(|&n| n > 0);
+        self.cycle = 0;
+    }
+
+    /// Run one scan cycle over all channels. The channel configuration of the ADC is restored afterwards.
+    pub fn scan<BUS, E>(&mut self, adc: &mut ADS122x04<BUS>) -> Result<ScanResult<N>, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        self.scan_restoring(adc, |_, _| Ok(()))
+    }
+
+    /// Run one scan cycle over all channels with an external multiplexer expanding the inputs: for channels with
+    /// an external input, the multiplexer is switched, the settling time is inserted and the first conversion
+    /// after the switch is discarded. Returns `Error::PinError` if a select line cannot be driven.
+    pub fn scan_with_external_mux<BUS, E, P, D, const K: usize>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        external: &mut ExternalMux<P, K>,
+        delay: &mut D,
+    ) -> Result<ScanResult<N>, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+            P: OutputPin,
+            D: DelayNs,
+    {
+        self.scan_restoring(adc, |adc, channel| select_external_input(adc, channel, external, delay))
+    }
+
+    fn scan_restoring<BUS, E>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        before: impl FnMut(&mut ADS122x04<BUS>, &Channel) -> Result<(), Error<E>>,
+    ) -> Result<ScanResult<N>, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let previous_mux = adc.config.mux;
+        let previous_gain = adc.config.gain;
+        let result = self.run(adc, before);
+        adc.set_input_mux(previous_mux)?;
+        adc.set_gain(previous_gain)?;
+        result
+    }
+
+    fn run<BUS, E>(
+        &mut self,
+        adc: &mut ADS122x04<BUS>,
+        mut before: impl FnMut(&mut ADS122x04<BUS>, &Channel) -> Result<(), Error<E>>,
+    ) -> Result<ScanResult<N>, Error<E>>
+        where
+            BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+    {
+        let timestamp = adc.timestamp();
+        let mut values = [0; N];
+        let mut converted = [0.0; N];
+        for ((value, converted), channel) in values.iter_mut().zip(converted.iter_mut()).zip(self.channels.iter()) {
+            select_channel(adc, channel, self.settling_discards)?;
+            before(adc, channel)?;
+            *value = adc.measure()?;
+            *converted = channel.convert(adc.convert_raw_to_voltage(*value));
+        }
+        let temperature = match self.temperature_interval {
+            Some(interval) if self.cycle.is_multiple_of(interval) => Some(adc.measure_temperature()?),
+            _ => None,
+        };
+        self.cycle = self.cycle.wrapping_add(1);
+        Ok(ScanResult {
+            values,
+            converted,
+            temperature,
+            timestamp,
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Result of a sensor scan cycle
+pub struct SensorScanResult<const N: usize> {
+    /// raw ADC values of the sensors with the offset subtracted
+    pub values: [i32; N],
+    /// readings in engineering units, `None` if out of range for the sensor
+    pub converted: [Option<f32>; N],
+    /// timestamp at the start of the cycle from the clock set with `ADS122x04::set_clock()`, if any
+    pub timestamp: Option<u64>,
+}
+
+/// Scan sequencer measuring a fixed set of heterogeneous sensors one after another, e.g. a bridge, a current loop
+/// and a thermocouple on the same device
+pub struct SensorScanner<'a, BUS, E, const N: usize> {
+    sensors: [&'a dyn Sensor<BUS, E>; N],
+}
+
+impl<'a, BUS, E, const N: usize> SensorScanner<'a, BUS, E, N>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Create a new scanner for the given sensors
+    pub fn new(sensors: [&'a dyn Sensor<BUS, E>; N]) -> Self {
+        SensorScanner { sensors }
+    }
+
+    /// Run one scan cycle over all sensors: starting from the configuration of the ADC at the start of the cycle,
+    /// every sensor is configured, its settling time (`Sensor::settling_us()`) is inserted and a measurement is
+    /// converted to engineering units. The configuration of the ADC is restored afterwards.
+    pub fn scan<D: DelayNs>(
+        &self,
+        adc: &mut ADS122x04<BUS>,
+        delay: &mut D,
+    ) -> Result<SensorScanResult<N>, Error<E>> {
+        let timestamp = adc.timestamp();
+        let config = adc.config();
+        adc.with_temporary_config(config, |adc| {
+            let mut values = [0; N];
+            let mut converted = [None; N];
+            for ((value, converted), sensor) in values.iter_mut().zip(converted.iter_mut()).zip(self.sensors.iter()) {
+                prepare_sensor(adc, config, *sensor, |us| delay.delay_us(us))?;
+                *value = adc.measure()?;
+                *converted = sensor.convert_raw(adc, *value)?;
+            }
+            Ok(SensorScanResult {
+                values,
+                converted,
+                timestamp,
+            })
+        })
+    }
+}
+
+/// Select the input and gain of `channel` and discard at least `settling_discards` conversions after the switch
+pub(crate) fn select_channel<BUS, E>(
+    adc: &mut ADS122x04<BUS>,
+    channel: &Channel,
+    settling_discards: Option<u8>,
+) -> Result<(), Error<E>>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    adc.set_input_mux(channel.mux)?;
+    adc.set_gain(channel.gain)?;
+    if let Some(count) = settling_discards {
+        adc.discard_next(count);
+    }
+    Ok(())
+}
+
+/// Switch the external multiplexer to the external input of `channel`, if any, then insert its settling time and
+/// discard the first conversion after the switch
+pub(crate) fn select_external_input<BUS, E, P, D, const K: usize>(
+    adc: &mut ADS122x04<BUS>,
+    channel: &Channel,
+    external: &mut ExternalMux<P, K>,
+    delay: &mut D,
+) -> Result<(), Error<E>>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+        P: OutputPin,
+        D: DelayNs,
+{
+    let Some(input) = channel.external_input else {
+        return Ok(());
+    };
+    if external.select(input).map_err(|_| Error::PinError)? {
+        delay.delay_us(external.settling_us);
+        adc.measure()?;
+    }
+    Ok(())
+}
+
+/// Configure `sensor` starting from `base`, not from the excitation of the previously measured sensor, and wait for
+/// its settling time
+pub(crate) fn prepare_sensor<BUS, E>(
+    adc: &mut ADS122x04<BUS>,
+    base: DeviceConfig,
+    sensor: &dyn Sensor<BUS, E>,
+    mut settle: impl FnMut(u32),
+) -> Result<(), Error<E>>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    adc.apply_config(base)?;
+    sensor.configure(adc)?;
+    settle(sensor.settling_us());
+    Ok(())
+}
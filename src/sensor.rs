@@ -0,0 +1,322 @@
+//! Common interface of the sensor front-end helpers
+//!
+//! The `Sensor` trait is object safe, so heterogeneous sensors on the same device can be stored as
+//! `&dyn Sensor<BUS, E>` and configured, read and converted uniformly, e.g. by `scan::SensorScanner` and
+//! `logger::DataLogger::poll_sensors()`.
+
+use crate::bridge::{Bridge, StrainGauge};
+use crate::current_loop::{CurrentLoop, LoopStatus};
+use crate::divider::DividerInput;
+use crate::ph::PhProbe;
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::rtd::Rtd;
+use crate::scale::ScaleStatus;
+#[cfg(any(
+    feature = "thermocouple-j",
+    feature = "thermocouple-t",
+    feature = "thermocouple-e"
+))]
+use crate::thermocouple::ThermocoupleType;
+use crate::thermistor::Thermistor;
+use crate::{Error, ADS122x04};
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Quantity the conversion of a sensor expects
+pub enum SensorInput {
+    /// input voltage (V)
+    Volts,
+    /// fraction of the full scale, corrected for the gain (ratiometric measurements)
+    Ratio,
+}
+
+/// Sensor front-end connected to the ADC
+pub trait Sensor<BUS, E>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Configure the ADC for this sensor (MUX, gain, reference, excitation)
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>>;
+
+    /// Time in us the sensor needs to settle after `configure()` before the first reading, inserted by
+    /// `SensorScanner` and `DataLogger::poll_sensors()`
+    fn settling_us(&self) -> u32 {
+        0
+    }
+
+    /// Quantity expected by `convert_reading()`
+    fn input(&self) -> SensorInput;
+
+    /// Convert the reading to engineering units, `None` if it is out of range for the sensor
+    fn convert_reading(&self, reading: f32) -> Option<f32>;
+
+    /// Convert a raw ADC value (offset subtracted) taken with the configuration of this sensor to engineering
+    /// units. Sensors needing an auxiliary measurement (e.g. the cold junction of a thermocouple) take it here.
+    fn convert_raw(&self, adc: &mut ADS122x04<BUS>, raw: i32) -> Result<Option<f32>, Error<E>> {
+        let reading = match self.input() {
+            SensorInput::Volts => adc.convert_raw_to_voltage(raw),
+            SensorInput::Ratio => adc.convert_raw_to_ratio(raw),
+        };
+        Ok(self.convert_reading(reading))
+    }
+
+    /// Take a measurement with the current configuration and convert it to engineering units
+    fn read_sensor(&self, adc: &mut ADS122x04<BUS>) -> Result<Option<f32>, Error<E>> {
+        let raw = adc.measure()?;
+        self.convert_raw(adc, raw)
+    }
+}
+
+/// The reading is `None` if the load is outside of the scale range and clamping is disabled
+impl<BUS, E> Sensor<BUS, E> for Bridge
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        Bridge::configure(self, adc)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Ratio
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        let scaled = self.convert(reading * 1000.0);
+        (scaled.status == ScaleStatus::InRange || self.scale.clamp).then_some(scaled.value)
+    }
+}
+
+/// The reading is the strain in µε
+impl<BUS, E> Sensor<BUS, E> for StrainGauge
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        StrainGauge::configure(self, adc)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Ratio
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        Some(self.convert(reading))
+    }
+}
+
+/// The reading is `None` on a loop fault, or if the current is outside of 4–20 mA and clamping is disabled
+impl<BUS, E> Sensor<BUS, E> for CurrentLoop
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        CurrentLoop::configure(self, adc)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Volts
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        let loop_reading = self.convert(reading / self.sense_resistor * 1000.0);
+        let in_range = loop_reading.value.status == ScaleStatus::InRange || self.scale.clamp;
+        (loop_reading.status == LoopStatus::Ok && in_range).then_some(loop_reading.value.value)
+    }
+}
+
+impl<BUS, E> Sensor<BUS, E> for DividerInput
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        DividerInput::configure(self, adc)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Volts
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        Some(self.convert(reading))
+    }
+}
+
+/// The reading is a single conversion at 25 °C, use `PhProbe::read()` for the averaged reading
+impl<BUS, E> Sensor<BUS, E> for PhProbe
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        PhProbe::configure(self, adc)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Volts
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        Some(self.convert(reading * 1000.0, 25.0))
+    }
+}
+
+impl<BUS, E> Sensor<BUS, E> for Rtd
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        Rtd::configure(self, adc)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Ratio
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        self.temperature(self.resistance(reading))
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Thermistor divider on an input of the ADC, measured ratiometrically against the divider excitation
+pub struct ThermistorSensor {
+    /// input the divider output is connected to, e.g. `Mux::Ain0Avss`
+    pub mux: Mux,
+    /// reference the divider is excited from, `VRef::AnalogSupply` for a divider between AVDD and AVSS
+    pub v_ref: VRef,
+    /// thermistor and divider
+    pub thermistor: Thermistor,
+}
+
+impl ThermistorSensor {
+    /// Create a thermistor divider input excited from the reference `v_ref`
+    pub fn new(mux: Mux, v_ref: VRef, thermistor: Thermistor) -> Self {
+        ThermistorSensor {
+            mux,
+            v_ref,
+            thermistor,
+        }
+    }
+}
+
+/// The divider is measured at gain 1, with the PGA bypassed for a single-ended input so the range extends down
+/// to AVSS
+impl<BUS, E> Sensor<BUS, E> for ThermistorSensor
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        adc.set_input_mux(self.mux)?;
+        adc.set_gain(Gain::Gain1)?;
+        adc.set_pga_bypass(matches!(self.mux.inputs(), Some((_, AnalogInput::Avss))))?;
+        adc.set_vref(self.v_ref)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Ratio
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        self.thermistor.temperature(reading)
+    }
+}
+
+#[cfg(any(
+    feature = "thermocouple-j",
+    feature = "thermocouple-t",
+    feature = "thermocouple-e"
+))]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Source of the cold junction temperature of a thermocouple
+pub enum ColdJunction {
+    /// internal temperature sensor of the ADC, measured after every thermocouple reading.
+    /// The cold junction has to be thermally coupled to the ADC.
+    InternalSensor,
+    /// fixed cold junction temperature in °C, e.g. an ice bath or an isothermal block at a known temperature
+    Fixed(f32),
+}
+
+#[cfg(any(
+    feature = "thermocouple-j",
+    feature = "thermocouple-t",
+    feature = "thermocouple-e"
+))]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Thermocouple on a differential input with cold junction compensation.
+/// The thermocouple has to be biased into the common-mode range of the PGA by the application.
+pub struct ThermocoupleSensor {
+    /// differential input the thermocouple is connected to
+    pub mux: Mux,
+    /// PGA gain
+    pub gain: Gain,
+    /// thermocouple type
+    pub kind: ThermocoupleType,
+    /// source of the cold junction temperature
+    pub cold_junction: ColdJunction,
+}
+
+#[cfg(any(
+    feature = "thermocouple-j",
+    feature = "thermocouple-t",
+    feature = "thermocouple-e"
+))]
+impl ThermocoupleSensor {
+    /// Create a thermocouple input compensated with the internal temperature sensor
+    pub fn new(mux: Mux, gain: Gain, kind: ThermocoupleType) -> Self {
+        ThermocoupleSensor {
+            mux,
+            gain,
+            kind,
+            cold_junction: ColdJunction::InternalSensor,
+        }
+    }
+
+    /// Use `cold_junction` instead of the internal temperature sensor
+    pub fn with_cold_junction(mut self, cold_junction: ColdJunction) -> Self {
+        self.cold_junction = cold_junction;
+        self
+    }
+}
+
+/// The thermocouple is measured against the internal 2.048 V reference. With `ColdJunction::InternalSensor`,
+/// `convert_reading()` cannot compensate and returns `None`, `convert_raw()` and `read_sensor()` measure the cold
+/// junction.
+#[cfg(any(
+    feature = "thermocouple-j",
+    feature = "thermocouple-t",
+    feature = "thermocouple-e"
+))]
+impl<BUS, E> Sensor<BUS, E> for ThermocoupleSensor
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    fn configure(&self, adc: &mut ADS122x04<BUS>) -> Result<(), Error<E>> {
+        adc.set_input_mux(self.mux)?;
+        adc.set_pga_bypass(false)?;
+        adc.set_gain(self.gain)?;
+        adc.set_vref(VRef::Internal)
+    }
+
+    fn input(&self) -> SensorInput {
+        SensorInput::Volts
+    }
+
+    fn convert_reading(&self, reading: f32) -> Option<f32> {
+        match self.cold_junction {
+            ColdJunction::InternalSensor => None,
+            ColdJunction::Fixed(cold_junction) => self.kind.compensated_temperature(reading * 1000.0, cold_junction),
+        }
+    }
+
+    fn convert_raw(&self, adc: &mut ADS122x04<BUS>, raw: i32) -> Result<Option<f32>, Error<E>> {
+        let millivolts = adc.convert_raw_to_voltage(raw) * 1000.0;
+        let cold_junction = match self.cold_junction {
+            ColdJunction::InternalSensor => adc.measure_temperature()?,
+            ColdJunction::Fixed(cold_junction) => cold_junction,
+        };
+        Ok(self.kind.compensated_temperature(millivolts, cold_junction))
+    }
+}
@@ -0,0 +1,71 @@
+//! Sinks decoupling the logger and streaming subsystems from storage and consoles
+
+#[cfg(feature = "heapless")]
+use core::convert::Infallible;
+use core::fmt::{self, Write};
+
+use crate::format::{FormatRecord, SampleWriter};
+#[cfg(feature = "heapless")]
+use crate::recorder::Recorder;
+#[cfg(feature = "heapless")]
+use crate::ConversionResult;
+#[cfg(all(feature = "heapless", feature = "float"))]
+use crate::logger::LogRecord;
+
+/// Destination of the records produced by the logger and streaming subsystems
+pub trait SampleSink<R> {
+    /// Error type
+    type Error;
+    /// Hand a record to the sink
+    fn push(&mut self, record: &R) -> Result<(), Self::Error>;
+}
+
+impl<R: FormatRecord, W: Write> SampleSink<R> for SampleWriter<W> {
+    type Error = fmt::Error;
+
+    fn push(&mut self, record: &R) -> Result<(), Self::Error> {
+        self.write_record(record)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// The queue sink is full, the record has been dropped
+pub struct QueueFull;
+
+#[cfg(feature = "heapless")]
+impl<R: Clone, const N: usize> SampleSink<R> for heapless::Deque<R, N> {
+    type Error = QueueFull;
+
+    fn push(&mut self, record: &R) -> Result<(), Self::Error> {
+        self.push_back(record.clone()).map_err(|_| QueueFull)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> SampleSink<ConversionResult> for Recorder<N> {
+    type Error = Infallible;
+
+    fn push(&mut self, record: &ConversionResult) -> Result<(), Self::Error> {
+        self.record(*record);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// The record carries no conversion result (e.g. an internal temperature sensor reading of the data logger) and
+/// has not been stored
+pub struct NoConversion;
+
+/// Stores the conversion of a data logger record with the timestamp of the logger
+#[cfg(all(feature = "heapless", feature = "float"))]
+impl<const N: usize> SampleSink<LogRecord> for Recorder<N> {
+    type Error = NoConversion;
+
+    fn push(&mut self, record: &LogRecord) -> Result<(), Self::Error> {
+        let result = record.result.ok_or(NoConversion)?;
+        self.record_at(record.timestamp, result);
+        Ok(())
+    }
+}
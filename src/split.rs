@@ -0,0 +1,94 @@
+//! Configuration and data handles sharing one device
+//!
+//! `SharedDevice::split()` returns a `ConfigHandle` for the rarely used configuration path and a `DataHandle` for
+//! the data path, e.g. for different tasks of a cooperative scheduler. Every access borrows the device for the
+//! duration of one operation, so register writes and data reads never interleave: an access while the other
+//! handle is using the device returns `Error::Busy`. The handles are not `Sync`, use `cs::CsDevice` (`critical-section`
+//! feature) for access from interrupt handlers.
+
+use core::cell::RefCell;
+
+use crate::codec::DeviceConfig;
+use crate::interface::{ReadData, WriteData};
+use crate::{ConversionResult, Error, ADS122x04};
+
+/// Device shared between a `ConfigHandle` and a `DataHandle`
+pub struct SharedDevice<BUS> {
+    adc: RefCell<ADS122x04<BUS>>,
+}
+
+/// Handle changing the configuration of a `SharedDevice`
+pub struct ConfigHandle<'a, BUS> {
+    adc: &'a RefCell<ADS122x04<BUS>>,
+}
+
+/// Handle reading the conversions of a `SharedDevice`
+pub struct DataHandle<'a, BUS> {
+    adc: &'a RefCell<ADS122x04<BUS>>,
+}
+
+impl<BUS> SharedDevice<BUS> {
+    /// Share the device between a configuration and a data handle
+    pub fn new(adc: ADS122x04<BUS>) -> Self {
+        SharedDevice { adc: RefCell::new(adc) }
+    }
+
+    /// Split into the configuration and the data handle
+    pub fn split(&mut self) -> (ConfigHandle<'_, BUS>, DataHandle<'_, BUS>) {
+        (ConfigHandle { adc: &self.adc }, DataHandle { adc: &self.adc })
+    }
+
+    /// Destroy the shared device and return the driver
+    pub fn release(self) -> ADS122x04<BUS> {
+        self.adc.into_inner()
+    }
+}
+
+/// borrow the device for one operation, `Error::Busy` if it is in use by the other handle
+fn with_device<BUS, E, T>(
+    adc: &RefCell<ADS122x04<BUS>>,
+    f: impl FnOnce(&mut ADS122x04<BUS>) -> Result<T, Error<E>>,
+) -> Result<T, Error<E>> {
+    let mut adc = adc.try_borrow_mut().map_err(|_| Error::Busy)?;
+    f(&mut adc)
+}
+
+impl<BUS, E> ConfigHandle<'_, BUS>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Change the configuration, see `ADS122x04::configure()`
+    pub fn configure(&self, f: impl FnOnce(&mut DeviceConfig)) -> Result<(), Error<E>> {
+        with_device(self.adc, |adc| adc.configure(f))
+    }
+
+    /// The cached configuration
+    pub fn config(&self) -> Result<DeviceConfig, Error<E>> {
+        with_device(self.adc, |adc| Ok(adc.config()))
+    }
+
+    /// Run `f` with exclusive access to the driver, e.g. for calibration
+    pub fn with<T>(&self, f: impl FnOnce(&mut ADS122x04<BUS>) -> Result<T, Error<E>>) -> Result<T, Error<E>> {
+        with_device(self.adc, f)
+    }
+}
+
+impl<BUS, E> DataHandle<'_, BUS>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Check the data ready flag
+    pub fn data_ready(&self) -> Result<bool, Error<E>> {
+        with_device(self.adc, |adc| adc.get_data_ready())
+    }
+
+    /// Read the latest conversion result, see `ADS122x04::read_conversion()`
+    pub fn read_conversion(&self) -> Result<ConversionResult, Error<E>> {
+        with_device(self.adc, |adc| adc.read_conversion())
+    }
+
+    /// Take a measurement, see `ADS122x04::measure()`
+    pub fn measure(&self) -> Result<i32, Error<E>> {
+        with_device(self.adc, |adc| adc.measure())
+    }
+}
@@ -0,0 +1,221 @@
+//! Sample statistics
+
+use libm::{log2f, sqrtf};
+
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{Error, ADS122x04};
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Noise characterization with shorted inputs
+pub struct NoiseReport {
+    /// RMS noise in V
+    pub rms: f32,
+    /// peak-to-peak noise in V
+    pub peak_to_peak: f32,
+    /// effective resolution in bits, log2(full-scale range / RMS noise)
+    pub effective_bits: f32,
+    /// noise-free resolution in bits, log2(full-scale range / peak-to-peak noise)
+    pub noise_free_bits: f32,
+}
+
+/// resolution of the ADC in bits
+const RESOLUTION_BITS: f32 = 24.0;
+
+/// resolution in bits of the full-scale range relative to the noise, limited to the ADC resolution
+fn resolution_bits(full_scale_range: f32, noise: f32) -> f32 {
+    if noise > 0.0 {
+        log2f(full_scale_range / noise).min(RESOLUTION_BITS)
+    } else {
+        RESOLUTION_BITS
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Running statistics (Welford's algorithm) over raw codes or volts
+pub struct Statistics {
+    count: u32,
+    mean: f32,
+    m2: f32,
+    min: f32,
+    max: f32,
+}
+
+impl Statistics {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Statistics {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+        }
+    }
+
+    /// Add a sample
+    pub fn push(&mut self, x: f32) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f32;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    /// Number of samples
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Mean of the samples
+    pub fn mean(&self) -> f32 {
+        self.mean
+    }
+
+    /// Sample variance (0 for less than two samples)
+    pub fn variance(&self) -> f32 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f32
+        }
+    }
+
+    /// Sample standard deviation, i.e. the RMS noise
+    pub fn std_dev(&self) -> f32 {
+        sqrtf(self.variance())
+    }
+
+    /// Smallest sample
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    /// Largest sample
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// Peak-to-peak value of the samples
+    pub fn peak_to_peak(&self) -> f32 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.max - self.min
+        }
+    }
+
+    /// Statistics of the samples multiplied by `factor`, e.g. to convert raw codes to volts
+    pub fn scaled(&self, factor: f32) -> Statistics {
+        let (min, max) = if factor < 0.0 {
+            (self.max * factor, self.min * factor)
+        } else {
+            (self.min * factor, self.max * factor)
+        };
+        Statistics {
+            count: self.count,
+            mean: self.mean * factor,
+            m2: self.m2 * factor * factor,
+            min,
+            max,
+        }
+    }
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<BUS, E> ADS122x04<BUS>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Take `n` measurements and collect the statistics of the raw ADC values
+    pub fn collect_statistics(&mut self, n: u32) -> Result<Statistics, Error<E>> {
+        let mut statistics = Statistics::new();
+        for _ in 0..n {
+            statistics.push(self.measure()? as f32);
+        }
+        Ok(statistics)
+    }
+
+    /// Characterize the noise: the inputs are shorted internally, `n` samples are taken at the given
+    /// gain and data rate and the RMS noise and effective resolution are reported.
+    /// The input multiplexer, gain and data rate are restored afterwards.
+    pub fn measure_noise(&mut self, gain: Gain, rate: DataRate, n: u32) -> Result<NoiseReport, Error<E>> {
+        let (previous_mux, previous_gain, previous_rate) = (self.config.mux, self.config.gain, self.config.data_rate);
+        let statistics = self
+            .set_input_mux(Mux::Shorted)
+            .and_then(|_| self.set_gain(gain))
+            .and_then(|_| self.set_data_rate(rate))
+            .and_then(|_| self.collect_statistics(n));
+        self.set_input_mux(previous_mux)?;
+        self.set_gain(previous_gain)?;
+        self.set_data_rate(previous_rate)?;
+
+        let gain = gain.factor() as f32;
+        let v_ref = self.config.v_ref.to_voltage();
+        let full_scale_range = 2.0 * v_ref / gain;
+        let statistics = statistics?.scaled(v_ref / ((1 << 23) as f32) / gain);
+        Ok(NoiseReport {
+            rms: statistics.std_dev(),
+            peak_to_peak: statistics.peak_to_peak(),
+            effective_bits: resolution_bits(full_scale_range, statistics.std_dev()),
+            noise_free_bits: resolution_bits(full_scale_range, statistics.peak_to_peak()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn welford_mean_and_variance() {
+        let mut statistics = Statistics::new();
+        assert_eq!(statistics.variance(), 0.0);
+        assert_eq!(statistics.peak_to_peak(), 0.0);
+        for x in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            statistics.push(x);
+        }
+        assert_eq!(statistics.count(), 8);
+        assert_eq!(statistics.mean(), 5.0);
+        // sum of squared deviations 32 over n - 1 = 7
+        assert!((statistics.variance() - 32.0 / 7.0).abs() < 1e-5);
+        assert_eq!(statistics.peak_to_peak(), 7.0);
+    }
+
+    #[test]
+    fn large_offset_is_stable() {
+        // raw codes near full scale, the naive sum of squares loses the variance in f32
+        let mut statistics = Statistics::new();
+        for x in [8_000_001.0, 8_000_003.0, 8_000_005.0] {
+            statistics.push(x);
+        }
+        assert_eq!(statistics.mean(), 8_000_003.0);
+        assert!((statistics.variance() - 4.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn scaled_statistics() {
+        let mut statistics = Statistics::new();
+        for x in [1.0, 2.0, 3.0] {
+            statistics.push(x);
+        }
+        let scaled = statistics.scaled(-2.0);
+        assert_eq!(scaled.mean(), -4.0);
+        assert!((scaled.variance() - 4.0).abs() < 1e-6);
+        assert_eq!((scaled.min(), scaled.max()), (-6.0, -2.0));
+    }
+
+    #[test]
+    fn resolution_is_limited_to_the_adc() {
+        assert_eq!(resolution_bits(4.096, 0.0), RESOLUTION_BITS);
+        assert!((resolution_bits(4.096, 4.096 / 65536.0) - 16.0).abs() < 1e-4);
+    }
+}
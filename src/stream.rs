@@ -0,0 +1,206 @@
+//! Continuous-mode reader
+
+use core::ops::ControlFlow;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+
+use embedded_hal::digital::InputPin;
+
+use crate::filter::{Filter, NoFilter};
+use crate::interface::{ReadData, WriteData};
+use crate::registers::*;
+use crate::{ConversionResult, Error, ADS122x04};
+
+/// Reader for conversions in continuous conversion mode, created by `ADS122x04::start_continuous()`.
+/// A streaming filter can be attached with `with_filter()` and the output rate reduced with `with_decimation()`.
+pub struct ContinuousReader<'a, BUS, F = NoFilter> {
+    adc: &'a mut ADS122x04<BUS>,
+    filter: F,
+    decimation: u32,
+    average: bool,
+    /// filtered conversions of the current decimation cycle
+    count: u32,
+    sum: i64,
+    saturated: bool,
+}
+
+impl<BUS, E> ADS122x04<BUS>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Switch to continuous conversion mode, start the conversions and return a reader for them
+    pub fn start_continuous(&mut self) -> Result<ContinuousReader<'_, BUS>, Error<E>> {
+        self.set_conversion_mode(ConversionMode::Continuous)?;
+        self.start()?;
+        Ok(ContinuousReader {
+            adc: self,
+            filter: NoFilter,
+            decimation: 1,
+            average: false,
+            count: 0,
+            sum: 0,
+            saturated: false,
+        })
+    }
+
+    /// Run continuous conversions and pass every conversion to `f`, polling the data ready flag.
+    /// When `f` returns `ControlFlow::Break`, the conversions are stopped, the device is powered down and the
+    /// break value is returned. The device is also stopped and powered down if a read fails.
+    pub fn run_continuous<B>(&mut self, f: impl FnMut(ConversionResult) -> ControlFlow<B>) -> Result<B, Error<E>> {
+        self.run_loop(f, |adc| {
+            adc.discard_unsettled()?;
+            adc.wait_data_ready()?;
+            adc.read_conversion()
+        })
+    }
+
+    /// Like `run_continuous()`, but waits for the data ready (DRDY) pin instead of polling the data ready flag.
+    /// If the pin does not signal new data in time, the device is powered down and `Error::Timeout` is returned.
+    pub fn run_continuous_with_drdy_pin<P: InputPin, B>(
+        &mut self,
+        drdy: &mut P,
+        f: impl FnMut(ConversionResult) -> ControlFlow<B>,
+    ) -> Result<B, Error<E>> {
+        self.run_loop(f, |adc| {
+            adc.discard_unsettled()?;
+            adc.wait_data_ready_pin(drdy)?;
+            adc.read_conversion()
+        })
+    }
+
+    fn run_loop<B>(
+        &mut self,
+        mut f: impl FnMut(ConversionResult) -> ControlFlow<B>,
+        mut next: impl FnMut(&mut Self) -> Result<ConversionResult, Error<E>>,
+    ) -> Result<B, Error<E>> {
+        self.set_conversion_mode(ConversionMode::Continuous)?;
+        self.start()?;
+        let outcome = loop {
+            match next(self) {
+                Ok(result) => {
+                    if let ControlFlow::Break(value) = f(result) {
+                        break Ok(value);
+                    }
+                }
+                Err(e) => break Err(e),
+            }
+        };
+        let stopped = self
+            .set_conversion_mode(ConversionMode::SingleShot)
+            .and_then(|_| self.power_down());
+        let value = outcome?;
+        stopped.map(|_| value)
+    }
+}
+
+impl<'a, BUS, E, F> ContinuousReader<'a, BUS, F>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+        F: Filter,
+{
+    /// Attach a streaming filter (e.g. `MovingAverage` or `LowPass`) applied to every conversion
+    pub fn with_filter<G: Filter>(self, filter: G) -> ContinuousReader<'a, BUS, G> {
+        ContinuousReader {
+            adc: self.adc,
+            filter,
+            decimation: self.decimation,
+            average: self.average,
+            count: 0,
+            sum: 0,
+            saturated: false,
+        }
+    }
+
+    /// Only return every `factor`-th conversion. All conversions still pass through the attached filter.
+    /// If `average` is set, the mean of the `factor` filtered conversions is returned instead of the last one.
+    pub fn with_decimation(mut self, factor: u32, average: bool) -> Self {
+        self.decimation = factor.max(1);
+        self.average = average;
+        self.count = 0;
+        self.sum = 0;
+        self.saturated = false;
+        self
+    }
+
+    /// Access the attached filter
+    pub fn filter_mut(&mut self) -> &mut F {
+        &mut self.filter
+    }
+
+    /// Wait for the next conversion and read it, the raw value is passed through the attached filter.
+    /// With decimation, this consumes `factor` conversions per call.
+    pub fn next_conversion(&mut self) -> Result<ConversionResult, Error<E>> {
+        loop {
+            self.adc.discard_unsettled()?;
+            self.adc.wait_data_ready()?;
+            if let Some(result) = self.read_decimated()? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// read the available conversion, pass it through the filter and return the output of the decimation
+    /// once `factor` conversions have been read
+    fn read_decimated(&mut self) -> Result<Option<ConversionResult>, Error<E>> {
+        let mut result = self.adc.read_conversion()?;
+        result.raw = self.filter.update(result.raw);
+        self.count += 1;
+        if self.count < self.decimation {
+            self.sum += result.raw as i64;
+            self.saturated |= result.is_saturated();
+            return Ok(None);
+        }
+        if self.average {
+            result.raw = ((self.sum + result.raw as i64) / self.decimation as i64) as i32;
+            result.saturated |= self.saturated;
+        }
+        self.count = 0;
+        self.sum = 0;
+        self.saturated = false;
+        Ok(Some(result))
+    }
+
+    /// Stop the continuous conversions by switching back to single-shot mode
+    pub fn stop(self) -> Result<(), Error<E>> {
+        self.adc.set_conversion_mode(ConversionMode::SingleShot)
+    }
+}
+
+/// **Polling adapter, not an asynchronous driver.** Every poll runs blocking bus transactions (the DRDY check and
+/// the data read), and while no conversion is ready the task wakes itself up immediately, so the executor
+/// busy-polls the bus and the CPU never sleeps while the stream is awaited. It lets embassy-style applications
+/// consume the conversions with `stream.next().await`, but does not free the CPU for other tasks between
+/// conversions: pace the polls yourself, e.g. by awaiting a DRDY pin interrupt or a timer of one conversion
+/// period before each `next()`.
+#[cfg(feature = "async")]
+impl<BUS, E, F> futures_core::Stream for ContinuousReader<'_, BUS, F>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+        F: Filter + Unpin,
+{
+    type Item = Result<ConversionResult, Error<E>>;
+
+    /// Check the data ready flag once per poll and read the conversion if it is available. The bus access is
+    /// blocking, while waiting for a conversion the task wakes itself up again, so the executor keeps polling
+    /// (busy-waits) until the conversion is ready.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let reader = self.get_mut();
+        let result = reader.adc.get_data_ready().and_then(|ready| {
+            if !ready || reader.adc.discard_ready_unsettled()? {
+                Ok(None)
+            } else {
+                reader.read_decimated()
+            }
+        });
+        match result {
+            Ok(None) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Ok(Some(result)) => Poll::Ready(Some(Ok(result))),
+            Err(e) => Poll::Ready(Some(Err(e))),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! NTC thermistor conversion from a ratiometric divider measurement
+
+use libm::logf;
+
+/// 0 °C in Kelvin
+const ZERO_CELSIUS: f32 = 273.15;
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Thermistor model used for the resistance to temperature conversion
+pub enum ThermistorModel {
+    /// Beta model with the nominal resistance at 25 °C (Ohm) and the beta coefficient (K)
+    Beta {
+        /// resistance at 25 °C in Ohm
+        r25: f32,
+        /// beta coefficient in K
+        beta: f32,
+    },
+    /// Steinhart–Hart model: 1/T = A + B ln(R) + C ln(R)^3
+    SteinhartHart {
+        /// coefficient A
+        a: f32,
+        /// coefficient B
+        b: f32,
+        /// coefficient C
+        c: f32,
+    },
+}
+
+impl ThermistorModel {
+    /// Convert the thermistor resistance (Ohm) to temperature (°C)
+    pub fn temperature(&self, resistance: f32) -> f32 {
+        let inv_t = match *self {
+            ThermistorModel::Beta { r25, beta } => {
+                1.0 / (25.0 + ZERO_CELSIUS) + logf(resistance / r25) / beta
+            }
+            ThermistorModel::SteinhartHart { a, b, c } => {
+                let ln_r = logf(resistance);
+                a + b * ln_r + c * ln_r * ln_r * ln_r
+            }
+        };
+        1.0 / inv_t - ZERO_CELSIUS
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Position of the thermistor in the voltage divider
+pub enum DividerPosition {
+    /// thermistor between the measured node and ground, fixed resistor to the excitation
+    LowSide,
+    /// thermistor between the excitation and the measured node, fixed resistor to ground
+    HighSide,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// NTC thermistor in a voltage divider with a fixed resistor
+pub struct Thermistor {
+    /// thermistor model
+    pub model: ThermistorModel,
+    /// fixed divider resistor in Ohm
+    pub r_fixed: f32,
+    /// position of the thermistor in the divider
+    pub position: DividerPosition,
+}
+
+impl Thermistor {
+    /// Calculate the thermistor resistance (Ohm) from the ratio of the divider output to the excitation voltage.
+    /// Returns `None` if the ratio is outside of (0, 1), e.g. for an open or shorted sensor.
+    pub fn resistance(&self, ratio: f32) -> Option<f32> {
+        if ratio <= 0.0 || ratio >= 1.0 {
+            return None;
+        }
+        match self.position {
+            DividerPosition::LowSide => Some(self.r_fixed * ratio / (1.0 - ratio)),
+            DividerPosition::HighSide => Some(self.r_fixed * (1.0 - ratio) / ratio),
+        }
+    }
+
+    /// Calculate the temperature (°C) from the ratio of the divider output to the excitation voltage
+    pub fn temperature(&self, ratio: f32) -> Option<f32> {
+        self.resistance(ratio).map(|r| self.model.temperature(r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libm::{cbrtf, expf, sqrtf};
+
+    const BETA: ThermistorModel = ThermistorModel::Beta { r25: 10_000.0, beta: 3950.0 };
+    const STEINHART_HART: ThermistorModel = ThermistorModel::SteinhartHart {
+        a: 1.009_249_5e-3,
+        b: 2.378_405_4e-4,
+        c: 2.019_202_7e-7,
+    };
+
+    /// resistance (Ohm) of the thermistor at the given temperature (°C), the inverse of the model
+    fn resistance(model: ThermistorModel, celsius: f32) -> f32 {
+        let inv_t = 1.0 / (celsius + ZERO_CELSIUS);
+        match model {
+            ThermistorModel::Beta { r25, beta } => r25 * expf(beta * (inv_t - 1.0 / (25.0 + ZERO_CELSIUS))),
+            ThermistorModel::SteinhartHart { a, b, c } => {
+                let x = (a - inv_t) / c;
+                let y = sqrtf((b / (3.0 * c)) * (b / (3.0 * c)) * (b / (3.0 * c)) + x * x / 4.0);
+                expf(cbrtf(y - x / 2.0) - cbrtf(y + x / 2.0))
+            }
+        }
+    }
+
+    #[test]
+    fn beta_model() {
+        assert!((BETA.temperature(10_000.0) - 25.0).abs() < 0.001);
+        // R(0 °C) = 10 kOhm * exp(3950 K * (1 / 273.15 K - 1 / 298.15 K))
+        assert!((BETA.temperature(33_620.6) - 0.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn steinhart_hart_model() {
+        // 1 / (A + B ln(10 kOhm) + C ln(10 kOhm)^3) - 273.15 K
+        assert!((STEINHART_HART.temperature(10_000.0) - 24.681).abs() < 0.01);
+    }
+
+    #[test]
+    fn round_trip() {
+        for model in [BETA, STEINHART_HART] {
+            for celsius in [-40.0, -10.0, 0.0, 25.0, 60.0, 100.0, 150.0] {
+                let temperature = model.temperature(resistance(model, celsius));
+                assert!((temperature - celsius).abs() < 0.01, "{:?} {} °C: {} °C", model, celsius, temperature);
+            }
+        }
+    }
+
+    #[test]
+    fn divider() {
+        for position in [DividerPosition::LowSide, DividerPosition::HighSide] {
+            let thermistor = Thermistor { model: BETA, r_fixed: 10_000.0, position };
+            assert!((thermistor.temperature(0.5).unwrap() - 25.0).abs() < 0.001);
+            assert_eq!(thermistor.resistance(0.0), None);
+            assert_eq!(thermistor.resistance(1.0), None);
+        }
+        let low_side = Thermistor { model: BETA, r_fixed: 10_000.0, position: DividerPosition::LowSide };
+        assert!((low_side.resistance(0.25).unwrap() - 10_000.0 / 3.0).abs() < 0.01);
+        let high_side = Thermistor { position: DividerPosition::HighSide, ..low_side };
+        assert!((high_side.resistance(0.25).unwrap() - 30_000.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,395 @@
+//! Thermocouple linearization according to the NIST ITS-90 reference functions
+//!
+//! Each thermocouple type is behind its own feature (`thermocouple-j`, `thermocouple-t`,
+//! `thermocouple-e`) so unused coefficient tables do not end up in flash.
+
+/// Polynomial valid in a given input range
+struct Range {
+    min: f32,
+    max: f32,
+    coefficients: &'static [f32],
+}
+
+/// Evaluate the piecewise polynomial with the Horner scheme
+fn evaluate(ranges: &[Range], x: f32) -> Option<f32> {
+    ranges
+        .iter()
+        .find(|range| x >= range.min && x <= range.max)
+        .map(|range| {
+            range
+                .coefficients
+                .iter()
+                .rev()
+                .fold(0.0, |acc, c| acc * x + c)
+        })
+}
+
+#[cfg(feature = "thermocouple-j")]
+#[allow(clippy::excessive_precision)]
+mod type_j {
+    use super::Range;
+
+    /// temperature (°C) to voltage (mV)
+    pub(super) const DIRECT: [Range; 2] = [
+        Range {
+            min: -210.0,
+            max: 760.0,
+            coefficients: &[
+                0.0,
+                5.0381187815E-02,
+                3.0475836930E-05,
+                -8.5681065720E-08,
+                1.3228195295E-10,
+                -1.7052958337E-13,
+                2.0948090697E-16,
+                -1.2538395336E-19,
+                1.5631725697E-23,
+            ],
+        },
+        Range {
+            min: 760.0,
+            max: 1200.0,
+            coefficients: &[
+                2.9645625681E+02,
+                -1.4976127786E+00,
+                3.1787103924E-03,
+                -3.1847686701E-06,
+                1.5720819004E-09,
+                -3.0691369056E-13,
+            ],
+        },
+    ];
+
+    /// voltage (mV) to temperature (°C)
+    pub(super) const INVERSE: [Range; 3] = [
+        Range {
+            min: -8.095,
+            max: 0.0,
+            coefficients: &[
+                0.0,
+                1.9528268E+01,
+                -1.2286185E+00,
+                -1.0752178E+00,
+                -5.9086933E-01,
+                -1.7256713E-01,
+                -2.8131513E-02,
+                -2.3963370E-03,
+                -8.3823321E-05,
+            ],
+        },
+        Range {
+            min: 0.0,
+            max: 42.919,
+            coefficients: &[
+                0.0,
+                1.978425E+01,
+                -2.001204E-01,
+                1.036969E-02,
+                -2.549687E-04,
+                3.585153E-06,
+                -5.344285E-08,
+                5.099890E-10,
+            ],
+        },
+        Range {
+            min: 42.919,
+            max: 69.553,
+            coefficients: &[
+                -3.11358187E+03,
+                3.00543684E+02,
+                -9.94773230E+00,
+                1.70276630E-01,
+                -1.43033468E-03,
+                4.73886084E-06,
+            ],
+        },
+    ];
+}
+
+#[cfg(feature = "thermocouple-t")]
+#[allow(clippy::excessive_precision)]
+mod type_t {
+    use super::Range;
+
+    /// temperature (°C) to voltage (mV)
+    pub(super) const DIRECT: [Range; 2] = [
+        Range {
+            min: -270.0,
+            max: 0.0,
+            coefficients: &[
+                0.0,
+                3.8748106364E-02,
+                4.4194434347E-05,
+                1.1844323105E-07,
+                2.0032973554E-08,
+                9.0138019559E-10,
+                2.2651156593E-11,
+                3.6071154205E-13,
+                3.8493939883E-15,
+                2.8213521925E-17,
+                1.4251594779E-19,
+                4.8768662286E-22,
+                1.0795539270E-24,
+                1.3945027062E-27,
+                7.9795153927E-31,
+            ],
+        },
+        Range {
+            min: 0.0,
+            max: 400.0,
+            coefficients: &[
+                0.0,
+                3.8748106364E-02,
+                3.3292227880E-05,
+                2.0618243404E-07,
+                -2.1882256846E-09,
+                1.0996880928E-11,
+                -3.0815758772E-14,
+                4.5479135290E-17,
+                -2.7512901673E-20,
+            ],
+        },
+    ];
+
+    /// voltage (mV) to temperature (°C)
+    pub(super) const INVERSE: [Range; 2] = [
+        Range {
+            min: -5.603,
+            max: 0.0,
+            coefficients: &[
+                0.0,
+                2.5949192E+01,
+                -2.1316967E-01,
+                7.9018692E-01,
+                4.2527777E-01,
+                1.3304473E-01,
+                2.0241446E-02,
+                1.2668171E-03,
+            ],
+        },
+        Range {
+            min: 0.0,
+            max: 20.872,
+            coefficients: &[
+                0.0,
+                2.592800E+01,
+                -7.602961E-01,
+                4.637791E-02,
+                -2.165394E-03,
+                6.048144E-05,
+                -7.293422E-07,
+            ],
+        },
+    ];
+}
+
+#[cfg(feature = "thermocouple-e")]
+#[allow(clippy::excessive_precision)]
+mod type_e {
+    use super::Range;
+
+    /// temperature (°C) to voltage (mV)
+    pub(super) const DIRECT: [Range; 2] = [
+        Range {
+            min: -270.0,
+            max: 0.0,
+            coefficients: &[
+                0.0,
+                5.8665508708E-02,
+                4.5410977124E-05,
+                -7.7998048686E-07,
+                -2.5800160843E-08,
+                -5.9452583057E-10,
+                -9.3214058667E-12,
+                -1.0287605534E-13,
+                -8.0370123621E-16,
+                -4.3979497391E-18,
+                -1.6414776355E-20,
+                -3.9673619516E-23,
+                -5.5827328721E-26,
+                -3.4657842013E-29,
+            ],
+        },
+        Range {
+            min: 0.0,
+            max: 1000.0,
+            coefficients: &[
+                0.0,
+                5.8665508710E-02,
+                4.5032275582E-05,
+                2.8908407212E-08,
+                -3.3056896652E-10,
+                6.5024403270E-13,
+                -1.9197495504E-16,
+                -1.2536600497E-18,
+                2.1489217569E-21,
+                -1.4388041782E-24,
+                3.5960899481E-28,
+            ],
+        },
+    ];
+
+    /// voltage (mV) to temperature (°C)
+    pub(super) const INVERSE: [Range; 2] = [
+        Range {
+            min: -8.825,
+            max: 0.0,
+            coefficients: &[
+                0.0,
+                1.6977288E+01,
+                -4.3514970E-01,
+                -1.5859697E-01,
+                -9.2502871E-02,
+                -2.6084314E-02,
+                -4.1360199E-03,
+                -3.4034030E-04,
+                -1.1564890E-05,
+            ],
+        },
+        Range {
+            min: 0.0,
+            max: 76.373,
+            coefficients: &[
+                0.0,
+                1.7057035E+01,
+                -2.3301759E-01,
+                6.5435585E-03,
+                -7.3562749E-05,
+                -1.7896001E-06,
+                8.4036165E-08,
+                -1.3735879E-09,
+                1.0629823E-11,
+                -3.2447087E-14,
+            ],
+        },
+    ];
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// Thermocouple type
+pub enum ThermocoupleType {
+    /// Type J (iron / constantan), -210 °C to 1200 °C
+    #[cfg(feature = "thermocouple-j")]
+    J,
+    /// Type T (copper / constantan), -270 °C to 400 °C
+    #[cfg(feature = "thermocouple-t")]
+    T,
+    /// Type E (chromel / constantan), -270 °C to 1000 °C
+    #[cfg(feature = "thermocouple-e")]
+    E,
+}
+
+impl ThermocoupleType {
+    /// Convert the thermoelectric voltage (mV) to temperature (°C).
+    /// Returns `None` if the voltage is outside the range of the reference function.
+    pub fn voltage_to_temperature(&self, millivolts: f32) -> Option<f32> {
+        match *self {
+            #[cfg(feature = "thermocouple-j")]
+            ThermocoupleType::J => evaluate(&type_j::INVERSE, millivolts),
+            #[cfg(feature = "thermocouple-t")]
+            ThermocoupleType::T => evaluate(&type_t::INVERSE, millivolts),
+            #[cfg(feature = "thermocouple-e")]
+            ThermocoupleType::E => evaluate(&type_e::INVERSE, millivolts),
+        }
+    }
+
+    /// Convert the temperature (°C) to the thermoelectric voltage (mV).
+    /// Returns `None` if the temperature is outside the range of the reference function.
+    pub fn temperature_to_voltage(&self, celsius: f32) -> Option<f32> {
+        match *self {
+            #[cfg(feature = "thermocouple-j")]
+            ThermocoupleType::J => evaluate(&type_j::DIRECT, celsius),
+            #[cfg(feature = "thermocouple-t")]
+            ThermocoupleType::T => evaluate(&type_t::DIRECT, celsius),
+            #[cfg(feature = "thermocouple-e")]
+            ThermocoupleType::E => evaluate(&type_e::DIRECT, celsius),
+        }
+    }
+
+    /// Convert the measured thermoelectric voltage (mV) to the hot junction temperature (°C),
+    /// compensating for the cold junction temperature (°C), e.g. from the internal temperature sensor.
+    pub fn compensated_temperature(&self, millivolts: f32, cold_junction: f32) -> Option<f32> {
+        self.temperature_to_voltage(cold_junction)
+            .and_then(|cj| self.voltage_to_temperature(millivolts + cj))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// check the reference points (°C, mV) of the NIST ITS-90 tables in both directions
+    fn check(kind: ThermocoupleType, table: &[(f32, f32)]) {
+        for &(celsius, millivolts) in table {
+            let voltage = kind.temperature_to_voltage(celsius).unwrap();
+            assert!((voltage - millivolts).abs() < 0.001, "{:?} {} °C: {} mV", kind, celsius, voltage);
+            let temperature = kind.voltage_to_temperature(millivolts).unwrap();
+            assert!((temperature - celsius).abs() < 0.1, "{:?} {} mV: {} °C", kind, millivolts, temperature);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "thermocouple-j")]
+    fn type_j_reference_points() {
+        check(
+            ThermocoupleType::J,
+            &[
+                (-200.0, -7.890),
+                (-100.0, -4.633),
+                (0.0, 0.0),
+                (100.0, 5.269),
+                (300.0, 16.327),
+                (500.0, 27.393),
+                (700.0, 39.132),
+                (900.0, 51.877),
+                (1100.0, 63.792),
+            ],
+        );
+        assert_eq!(ThermocoupleType::J.temperature_to_voltage(1300.0), None);
+        assert_eq!(ThermocoupleType::J.voltage_to_temperature(70.0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "thermocouple-t")]
+    fn type_t_reference_points() {
+        check(
+            ThermocoupleType::T,
+            &[
+                (-200.0, -5.603),
+                (-100.0, -3.379),
+                (0.0, 0.0),
+                (100.0, 4.279),
+                (200.0, 9.288),
+                (400.0, 20.872),
+            ],
+        );
+        assert_eq!(ThermocoupleType::T.temperature_to_voltage(450.0), None);
+        assert_eq!(ThermocoupleType::T.voltage_to_temperature(-6.0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "thermocouple-e")]
+    fn type_e_reference_points() {
+        check(
+            ThermocoupleType::E,
+            &[
+                (-200.0, -8.825),
+                (-100.0, -5.237),
+                (0.0, 0.0),
+                (100.0, 6.319),
+                (300.0, 21.036),
+                (500.0, 37.005),
+                (900.0, 68.787),
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "thermocouple-t")]
+    fn cold_junction_compensation() {
+        // 100 °C hot junction with the cold junction at 25 °C: 4.279 mV - 0.992 mV
+        let temperature = ThermocoupleType::T.compensated_temperature(3.287, 25.0).unwrap();
+        assert!((temperature - 100.0).abs() < 0.1, "{} °C", temperature);
+    }
+}
@@ -0,0 +1,30 @@
+//! Typed units API using `uom` quantities
+
+use uom::si::electric_potential::volt;
+use uom::si::f32::{ElectricPotential, ElectricalResistance, ThermodynamicTemperature};
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+use crate::interface::{ReadData, WriteData};
+use crate::{Error, ADS122x04};
+
+impl<BUS, E> ADS122x04<BUS>
+    where
+        BUS: ReadData<Error=Error<E>> + WriteData<Error=Error<E>>,
+{
+    /// Read the voltage of the ADC as a typed quantity
+    pub fn get_electric_potential(&mut self) -> Result<ElectricPotential, Error<E>> {
+        self.get_voltage().map(ElectricPotential::new::<volt>)
+    }
+
+    /// Measure the internal temperature sensor as a typed quantity
+    pub fn measure_thermodynamic_temperature(&mut self) -> Result<ThermodynamicTemperature, Error<E>> {
+        self.measure_temperature()
+            .map(ThermodynamicTemperature::new::<degree_celsius>)
+    }
+
+    /// Take a measurement of a resistance ratiometrically against a reference resistor,
+    /// e.g. an RTD excited by an IDAC with the reference resistor between REFP and REFN
+    pub fn read_resistance(&mut self, reference: ElectricalResistance) -> Result<ElectricalResistance, Error<E>> {
+        self.read_ratiometric().map(|ratio| reference * ratio)
+    }
+}
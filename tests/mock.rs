@@ -0,0 +1,214 @@
+//! Driver behaviour against the simulated device
+#![cfg(feature = "float")]
+
+use ads122x04::mock::MockAds122x04;
+use ads122x04::codec::DeviceConfig;
+use ads122x04::registers::*;
+use ads122x04::ADS122x04;
+
+#[test]
+fn microvolts_match_voltage() {
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    adc.set_vref_microvolts(VRef::External(0.0), 2_500_000).unwrap();
+    for gain in [Gain::Gain1, Gain::Gain4, Gain::Gain128] {
+        adc.set_gain(gain).unwrap();
+        for raw in [0, 1, -1, 1_234_567, -4_000_000, 0x7FFFFF, -0x800000] {
+            adc.mock().set_conversion(Mux::Ain0Ain1, raw);
+            let measured = adc.measure().unwrap();
+            let microvolts = adc.get_microvolts().unwrap();
+            let expected = adc.convert_raw_to_voltage(measured) * 1e6;
+            assert!(
+                (microvolts as f32 - expected).abs() <= 1.0,
+                "gain {:?} raw {}: {} uV, expected {} uV",
+                gain,
+                raw,
+                microvolts,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn logger_restores_reference_after_sensor() {
+    use ads122x04::bridge::Bridge;
+    use ads122x04::logger::{DataLogger, LogChannel, LogSource};
+    use ads122x04::scan::Channel;
+    use ads122x04::sensor::Sensor;
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+
+    fn clock() -> u64 {
+        0
+    }
+
+    fn by_reference(config: &DeviceConfig, _: u32) -> i32 {
+        match config.v_ref {
+            VRef::Internal => 1000,
+            _ => 2000,
+        }
+    }
+
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    adc.mock().set_generator(Some(by_reference));
+    let bridge = Bridge::new(Mux::Ain0Ain1, Gain::Gain128, 3.3, 2.0, 10.0).unwrap();
+    let sensors: [&dyn Sensor<_, _>; 1] = [&bridge];
+    let mut logger = DataLogger::new(
+        [
+            LogChannel::new(LogSource::Sensor(0), 10),
+            LogChannel::new(LogSource::Input(Channel::new(Mux::Ain2Avss, Gain::Gain1)), 10),
+        ],
+        clock,
+    );
+    let mut raw = [0; 2];
+    let count = logger
+        .poll_sensors(&mut adc, &sensors, &mut NoopDelay::new(), |record| {
+            raw[record.channel] = record.result.unwrap().raw;
+        })
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(raw, [2000, 1000]);
+    assert!(matches!(adc.mock().config().v_ref, VRef::Internal));
+}
+
+#[test]
+fn status_decodes_the_device_registers() {
+    use ads122x04::interface::WriteData;
+    use ads122x04::Error;
+
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    adc.set_conversion_mode(ConversionMode::Continuous).unwrap();
+    let status = adc.status().unwrap();
+    assert!(matches!(status.conversion_mode, ConversionMode::Continuous));
+
+    // reset behind the back of the driver
+    adc.mock().write_data(Commands::Reset as u8).unwrap();
+    assert_eq!(adc.status().unwrap_err(), Error::DeviceResetDetected);
+}
+
+#[test]
+fn unchanged_registers_are_not_written() {
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    // the registers are written once, as the state of the device is unknown
+    adc.configure(|c| {
+        c.mux(Mux::Ain2Ain3).gain(Gain::Gain16);
+    })
+    .unwrap();
+    assert_eq!(adc.mock().register_writes(), 4);
+    let writes = adc.mock().register_writes();
+    for _ in 0..10 {
+        adc.set_input_mux(Mux::Ain2Ain3).unwrap();
+        adc.set_gain(Gain::Gain16).unwrap();
+    }
+    assert_eq!(adc.mock().register_writes(), writes);
+
+    // MUX and gain share configuration register 0
+    adc.configure(|c| {
+        c.mux(Mux::Ain0Ain1).gain(Gain::Gain4).data_rate(DataRate::Sps90Normal);
+    })
+    .unwrap();
+    assert_eq!(adc.mock().register_writes(), writes + 2);
+    assert_eq!(adc.mock().config().gain as u8, Gain::Gain4 as u8);
+}
+
+#[test]
+fn conversions_are_discarded_after_a_mux_change() {
+    fn conversion_number(_: &DeviceConfig, n: u32) -> i32 {
+        n as i32
+    }
+
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    adc.mock().set_generator(Some(conversion_number));
+    adc.set_conversion_mode(ConversionMode::Continuous).unwrap();
+    adc.start().unwrap();
+    adc.measure().unwrap();
+
+    for discards in [1, 3] {
+        adc.set_settling_discards(discards);
+        let before = adc.mock().conversions();
+        adc.set_input_mux(Mux::Ain2Ain3).unwrap();
+        let raw = adc.measure().unwrap();
+        assert_eq!(adc.mock().conversions() - before, discards as u32 + 1);
+        assert_eq!(raw, before as i32 + discards as i32);
+        adc.set_input_mux(Mux::Ain0Ain1).unwrap();
+        adc.measure().unwrap();
+    }
+
+    // the MUX is unchanged, nothing to discard
+    let before = adc.mock().conversions();
+    adc.set_input_mux(Mux::Ain0Ain1).unwrap();
+    adc.measure().unwrap();
+    assert_eq!(adc.mock().conversions() - before, 1);
+}
+
+#[test]
+fn nack_is_retried_according_to_the_policy() {
+    use ads122x04::mock::{Fault, MockError};
+    use ads122x04::{Error, RetryPolicy};
+
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    adc.mock().inject_fault(Fault::Nack, 0).unwrap();
+    assert_eq!(adc.get_gain().unwrap_err(), Error::CommError(MockError::Nack));
+
+    adc.set_retry_policy(RetryPolicy { attempts: 3, backoff: 10 });
+    // two failed attempts are retried
+    adc.mock().inject_fault(Fault::Nack, 0).unwrap();
+    adc.mock().inject_fault(Fault::Nack, 0).unwrap();
+    adc.set_gain(Gain::Gain8).unwrap();
+    assert_eq!(adc.mock().register_writes(), 1);
+    assert_eq!(adc.get_gain().unwrap() as u8, Gain::Gain8 as u8);
+
+    // the third failed attempt is returned, the next transaction is not affected
+    for _ in 0..3 {
+        adc.mock().inject_fault(Fault::Nack, 0).unwrap();
+    }
+    assert_eq!(adc.get_gain().unwrap_err(), Error::CommError(MockError::Nack));
+    assert_eq!(adc.get_gain().unwrap() as u8, Gain::Gain8 as u8);
+}
+
+#[test]
+fn consistent_read_detects_torn_and_corrupted_data() {
+    use ads122x04::mock::Fault;
+    use ads122x04::Error;
+
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    adc.mock().set_conversion(Mux::Ain0Ain1, 1234);
+    adc.set_conversion_mode(ConversionMode::Continuous).unwrap();
+    adc.start().unwrap();
+
+    // a new conversion completes at every DRDY poll, so every read may be torn
+    assert_eq!(adc.read_conversion_consistent().unwrap_err(), Error::Timeout);
+
+    adc.mock().set_conversion_polls(5);
+    adc.start().unwrap();
+    adc.measure().unwrap();
+    assert_eq!(adc.read_conversion_consistent().unwrap().raw, 1234);
+
+    adc.set_crc(Crc::Crc16).unwrap();
+    adc.mock().inject_fault(Fault::CorruptedCrc, 0).unwrap();
+    assert!(matches!(adc.read_conversion_consistent(), Err(Error::CrcMismatch { .. })));
+    assert_eq!(adc.read_conversion_consistent().unwrap().raw, 1234);
+}
+
+#[test]
+fn bulk_configuration_rejects_bypassed_pga_at_high_gain() {
+    use ads122x04::Error;
+
+    let mut adc = ADS122x04::new_mock(MockAds122x04::new());
+    adc.configure(|c| {
+        c.gain(Gain::Gain4).pga_bypass(true);
+    })
+    .unwrap();
+    let writes = adc.mock().register_writes();
+    let conflict = adc.configure(|c| {
+        c.gain(Gain::Gain8).mux(Mux::Ain2Ain3);
+    });
+    assert!(matches!(conflict, Err(Error::ConfigConflict(_))));
+    assert_eq!(adc.mock().register_writes(), writes);
+    assert_eq!(adc.config().gain as u8, Gain::Gain4 as u8);
+
+    let mut config = adc.config();
+    config.pga_bypass(false).gain(Gain::Gain128);
+    assert!(config.validate::<()>().is_ok());
+    config.pga_bypass(true);
+    assert!(adc.with_temporary_config(config, |_| Ok(())).is_err());
+}
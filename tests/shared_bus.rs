@@ -0,0 +1,34 @@
+//! Two drivers sharing one I2C bus through embedded-hal-bus
+#![cfg(feature = "i2c")]
+
+use core::cell::RefCell;
+
+use ads122x04::registers::Gain;
+use ads122x04::ADS122x04;
+use embedded_hal_bus::i2c::RefCellDevice;
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+const WREG_0: u8 = 0x40;
+const RREG_0: u8 = 0x20;
+
+#[test]
+fn two_drivers_on_refcell_device() {
+    let gain_4 = (Gain::Gain4 as u8) << 1;
+    let gain_8 = (Gain::Gain8 as u8) << 1;
+    let expectations = [
+        Transaction::write(0x40, vec![WREG_0, gain_4]),
+        Transaction::write(0x41, vec![WREG_0, gain_8]),
+        Transaction::write_read(0x40, vec![RREG_0], vec![gain_4]),
+        Transaction::write_read(0x41, vec![RREG_0], vec![gain_8]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let bus = RefCell::new(i2c.clone());
+    let mut first = ADS122x04::new_i2c(0x40, RefCellDevice::new(&bus));
+    let mut second = ADS122x04::new_i2c(0x41, RefCellDevice::new(&bus));
+
+    first.set_gain(Gain::Gain4).unwrap();
+    second.set_gain(Gain::Gain8).unwrap();
+    assert_eq!(first.get_gain().unwrap(), Gain::Gain4);
+    assert_eq!(second.get_gain().unwrap(), Gain::Gain8);
+    i2c.done();
+}
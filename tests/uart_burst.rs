@@ -0,0 +1,103 @@
+//! Burst read over a simulated UART
+#![cfg(feature = "uart")]
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+
+use ads122x04::codec::{encode_data_frame, DataFrame, MAX_DATA_FRAME_LEN};
+use ads122x04::registers::*;
+use ads122x04::{ConversionResult, Error, ADS122x04};
+use embedded_io::{ErrorType, Read, ReadReady, Write};
+
+/// UART returning prepared response bytes and recording the transmitted bytes
+#[derive(Default)]
+struct Uart {
+    rx: VecDeque<u8>,
+    tx: Vec<u8>,
+}
+
+impl Uart {
+    /// queue the data frame of a conversion as response, with the data byte `corrupt` flipped
+    fn respond(&mut self, data: u32, counter: u8, corrupt: Option<usize>) {
+        let mut buffer = [0; MAX_DATA_FRAME_LEN];
+        let frame = DataFrame { counter: Some(counter), data };
+        let len = encode_data_frame(&frame, Crc::Crc16, &mut buffer);
+        if let Some(byte) = corrupt {
+            buffer[1 + byte] ^= 0x01;
+        }
+        self.rx.extend(&buffer[..len]);
+    }
+}
+
+impl ErrorType for Uart {
+    type Error = Infallible;
+}
+
+impl Read for Uart {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut len = 0;
+        while len < buf.len() {
+            match self.rx.pop_front() {
+                Some(byte) => buf[len] = byte,
+                None => break,
+            }
+            len += 1;
+        }
+        Ok(len)
+    }
+}
+
+impl ReadReady for Uart {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+}
+
+impl Write for Uart {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.tx.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// driver with the data counter and CRC enabled, reading the responses queued in `uart`
+fn adc_with_crc(uart: Uart) -> ADS122x04<ads122x04::interface::SerialInterface<Uart>> {
+    let mut adc = ADS122x04::new_serial(uart);
+    adc.set_data_counter(true).unwrap();
+    adc.set_crc(Crc::Crc16).unwrap();
+    adc
+}
+
+#[test]
+fn burst_read_pipelines_the_commands() {
+    let mut uart = Uart::default();
+    for (counter, data) in [(1, 100), (2, 0xFFFF38), (3, 300)] {
+        uart.respond(data, counter, None);
+    }
+    let mut adc = adc_with_crc(uart);
+    let mut results = [ConversionResult::default(); 3];
+    adc.read_burst(&mut results).unwrap();
+    assert_eq!(results.map(|result| result.raw), [100, -200, 300]);
+    assert_eq!(results.map(|result| result.counter), [Some(1), Some(2), Some(3)]);
+
+    let uart = adc.release();
+    assert!(uart.rx.is_empty());
+    // all RDATA commands follow the register writes
+    let rdata = [0x55, Commands::RData as u8];
+    assert!(uart.tx.ends_with(&rdata.repeat(3)));
+}
+
+#[test]
+fn burst_read_detects_corrupted_data() {
+    let mut uart = Uart::default();
+    uart.respond(100, 1, None);
+    uart.respond(200, 2, Some(1));
+    let mut adc = adc_with_crc(uart);
+    let mut results = [ConversionResult::default(); 2];
+    assert!(matches!(adc.read_burst(&mut results), Err(Error::CrcMismatch { .. })));
+    assert_eq!(results[0].raw, 100);
+}